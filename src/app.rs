@@ -1,7 +1,42 @@
-use eframe::egui::{Context, FontData, FontDefinitions, FontFamily, FontId, TextStyle};
+use crate::batch_rename::BatchRenameDialog;
+use crate::clipboard::{
+    paste_destination, resolve_conflict, ClipboardMode, ConflictResolution, FileTransfer,
+    PendingPaste, TransferRequest, TransferStatus, TreeClipboard,
+};
+use crate::document::{self, LineEnding, TextEncoding};
+use crate::file_operations::move_to_trash;
+use crate::file_types::{editor_mode_for, EditorMode};
+use crate::ignore::IgnoreRules;
+use crate::tags::TagStore;
+use crate::text::columns::format_rulers;
+use crate::text::genko::ManuscriptReport;
+use crate::text::indent::IndentRules;
+use crate::text::lint::{lint, Diagnostic, LintConfig, Rule};
+use crate::text::stats::{Counts, DocumentStats, TrackedText};
+use crate::text::vertical::Column;
+use crate::tree::{
+    is_visible, list_children, matches_filter, rename_in_manual_order, SortMode, TreeOptions,
+};
+use crate::ui::aozora::AozoraExport;
+use crate::ui::central_panel::{EditorLayout, EDITOR_ID};
+use crate::ui::convert::ConversionPreview;
+use crate::ui::dialect::DialectConversion;
+use crate::ui::variants::VariantAnalysis;
+use crate::ui::vertical_preview::PreviewMode;
+use crate::workspace::{relative_key, resolve_key, WorkspaceState};
+use eframe::egui::text::{CCursor, CCursorRange};
+use eframe::egui::{
+    Context, FontData, FontDefinitions, FontFamily, FontId, Id, TextBuffer, TextEdit, TextStyle,
+};
 use eframe::App;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// 入力が止まってから校正するまでの秒数
+const LINT_DELAY: f64 = 0.3;
 
 pub struct TxtEditorApp {
     pub folder_path: Option<PathBuf>,
@@ -14,7 +49,9 @@ pub struct TxtEditorApp {
     pub file_modified: bool,
     pub new_file_popup: bool,
     pub new_file_name: String,
-    pub new_file_path: Option<PathBuf>,
+    pub new_file_parent: Option<PathBuf>,
+    pub new_file_type: usize,
+    pub new_file_template: usize,
     pub right_panel_file: Option<PathBuf>,
     pub right_panel_contents: String,
     pub new_folder_popup: bool,
@@ -24,6 +61,104 @@ pub struct TxtEditorApp {
     pub rename_target: Option<PathBuf>,
     pub new_name: String,
     pub selected_item: Option<PathBuf>,
+    pub tree_clipboard: Option<TreeClipboard>,
+    pub paste_conflict: Option<PendingPaste>,
+    pub file_transfer: Option<FileTransfer>,
+    pub transfer_queue: VecDeque<TransferRequest>,
+    pub drop_target_dir: Option<PathBuf>,
+    pub selected_items: Vec<PathBuf>,
+    // 前のフレームで表示した項目の順（描画中の一覧は rendered_items に集める）
+    pub visible_items: Vec<PathBuf>,
+    pub rendered_items: Vec<PathBuf>,
+    pub tags: TagStore,
+    pub tag_popup: bool,
+    pub new_tag: String,
+    pub batch_rename: Option<BatchRenameDialog>,
+    pub expanded_dirs: HashSet<PathBuf>,
+    pub tree_has_focus: bool,
+    pub focus_editor: bool,
+    pub scroll_to_selected: bool,
+    pub type_ahead: String,
+    pub type_ahead_time: f64,
+    pub tree_options: TreeOptions,
+    pub ignore_rules: IgnoreRules,
+    pub known_extensions: BTreeSet<String>,
+    pub editor_mode: EditorMode,
+    pub tree_scroll_offset: f32,
+    pub restore_tree_scroll: Option<f32>,
+    pub saved_workspace_state: String,
+    pub last_state_save: f64,
+    pub children_cache: HashMap<PathBuf, CachedDir>,
+    pub page_limits: HashMap<PathBuf, usize>,
+    pub tree_ancestors: Vec<PathBuf>,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+    pub file_size: u64,
+    pub save_error: Option<String>,
+    pub unsaved_prompt: Option<PendingOpen>,
+    pub failed_save: Option<(u64, TextEncoding)>,
+    pub stats: DocumentStats,
+    pub selection_range: Range<usize>,
+    pub selection_counts: Counts,
+    pub stats_window: bool,
+    pub manuscript_report: Option<ManuscriptReport>,
+    pub aozora_export: Option<AozoraExport>,
+    pub dialect_conversion: Option<DialectConversion>,
+    pub text_revision: u64,
+    pub vertical_preview: bool,
+    pub preview_mode: PreviewMode,
+    pub vertical_columns: Vec<Column>,
+    pub vertical_layout_key: Option<(u64, usize, PreviewMode)>,
+    pub exclude_markup: bool,
+    pub ruby_popup: bool,
+    pub ruby_target: Range<usize>,
+    pub ruby_reading: String,
+    pub cursor_line: usize,
+    pub cursor_column: usize,
+    pub pending_selection: Option<Range<usize>>,
+    pub goto_line_popup: bool,
+    pub goto_line_input: String,
+    pub lint_config: LintConfig,
+    pub diagnostics: Vec<Diagnostic>,
+    pub lint_revision: Option<u64>,
+    // 最後に文書が変わったときの版と時刻（入力が止まるまで校正を待つ）
+    pub last_edit: (u64, f64),
+    // 校正結果を入れ替えるたびに増やす（エディタのレイアウトの使い回しに使う）
+    pub diagnostics_revision: u64,
+    pub editor_layout: Option<EditorLayout>,
+    pub show_problems: bool,
+    pub lint_rules_window: bool,
+    pub variant_analysis: Option<VariantAnalysis>,
+    pub conversion_preview: Option<ConversionPreview>,
+    pub indent_rules: IndentRules,
+    pub show_whitespace: bool,
+    pub highlight_whitespace: bool,
+    pub show_line_numbers: bool,
+    pub visual_line_numbers: bool,
+    pub highlight_current_line: bool,
+    pub gutter_anchor: Option<usize>,
+    // 折り返す桁数（全角で数える、0 ならウィンドウの幅で折り返す）とルーラーの桁
+    pub wrap_columns: usize,
+    pub rulers: Vec<usize>,
+    pub rulers_input: String,
+}
+
+// 読み込んだフォルダの一覧（フォルダの更新日時が変わるまで使い回す）
+pub struct CachedDir {
+    modified: Option<SystemTime>,
+    pub canonical: PathBuf,
+    pub children: Vec<PathBuf>,
+}
+
+fn dir_modified(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir).and_then(|m| m.modified()).ok()
+}
+
+// 保存に失敗したときに保留する、開いている文書を置き換える操作
+pub enum PendingOpen {
+    File(PathBuf),
+    Encoding(TextEncoding),
+    Folder(PathBuf),
 }
 
 impl Default for TxtEditorApp {
@@ -39,7 +174,9 @@ impl Default for TxtEditorApp {
             file_modified: false,
             new_file_popup: false,
             new_file_name: String::new(),
-            new_file_path: None,
+            new_file_parent: None,
+            new_file_type: 0,
+            new_file_template: 0,
             right_panel_file: None,
             right_panel_contents: String::new(),
             new_folder_popup: false,
@@ -49,6 +186,82 @@ impl Default for TxtEditorApp {
             rename_target: None,
             new_name: String::new(),
             selected_item: None,
+            tree_clipboard: None,
+            paste_conflict: None,
+            file_transfer: None,
+            transfer_queue: VecDeque::new(),
+            drop_target_dir: None,
+            selected_items: Vec::new(),
+            visible_items: Vec::new(),
+            rendered_items: Vec::new(),
+            tags: TagStore::default(),
+            tag_popup: false,
+            new_tag: String::new(),
+            batch_rename: None,
+            expanded_dirs: HashSet::new(),
+            tree_has_focus: false,
+            focus_editor: false,
+            scroll_to_selected: false,
+            type_ahead: String::new(),
+            type_ahead_time: 0.0,
+            tree_options: TreeOptions::default(),
+            ignore_rules: IgnoreRules::default(),
+            known_extensions: BTreeSet::new(),
+            editor_mode: EditorMode::PlainText,
+            tree_scroll_offset: 0.0,
+            restore_tree_scroll: None,
+            saved_workspace_state: String::new(),
+            last_state_save: 0.0,
+            children_cache: HashMap::new(),
+            page_limits: HashMap::new(),
+            tree_ancestors: Vec::new(),
+            encoding: TextEncoding::Utf8,
+            line_ending: LineEnding::Lf,
+            file_size: 0,
+            save_error: None,
+            unsaved_prompt: None,
+            failed_save: None,
+            stats: DocumentStats::default(),
+            selection_range: 0..0,
+            selection_counts: Counts::default(),
+            stats_window: false,
+            manuscript_report: None,
+            aozora_export: None,
+            dialect_conversion: None,
+            text_revision: 0,
+            vertical_preview: false,
+            preview_mode: PreviewMode::Vertical,
+            vertical_columns: Vec::new(),
+            vertical_layout_key: None,
+            exclude_markup: false,
+            ruby_popup: false,
+            ruby_target: 0..0,
+            ruby_reading: String::new(),
+            cursor_line: 1,
+            cursor_column: 1,
+            pending_selection: None,
+            goto_line_popup: false,
+            goto_line_input: String::new(),
+            lint_config: LintConfig::default(),
+            diagnostics: Vec::new(),
+            lint_revision: None,
+            last_edit: (0, 0.0),
+            diagnostics_revision: 0,
+            editor_layout: None,
+            show_problems: false,
+            lint_rules_window: false,
+            variant_analysis: None,
+            conversion_preview: None,
+            indent_rules: IndentRules::default(),
+            show_whitespace: false,
+            highlight_whitespace: false,
+            show_line_numbers: true,
+            visual_line_numbers: false,
+            highlight_current_line: true,
+            gutter_anchor: None,
+            wrap_columns: 0,
+            rulers: Vec::new(),
+            rulers_input: String::new(),
         }
     }
 }
@@ -101,15 +314,607 @@ impl TxtEditorApp {
         self.fonts_set = true;
     }
 
-    pub fn save_file_if_modified(&mut self) {
-        if self.file_modified {
+    // 保存できなかった変更が残っていれば false を返す
+    pub fn save_file_if_modified(&mut self) -> bool {
+        // 同じ内容・文字コードで失敗した保存は、編集されるまで試し直さない
+        let attempt = (self.text_revision, self.encoding);
+        if self.file_modified && self.failed_save != Some(attempt) {
             if let Some(ref selected_file) = self.selected_file {
-                if let Err(err) = fs::write(selected_file, &self.file_contents) {
-                    eprintln!("Failed to save file: {}", err);
-                } else {
-                    self.file_modified = false;
+                match document::save(
+                    selected_file,
+                    &self.file_contents,
+                    self.encoding,
+                    self.line_ending,
+                ) {
+                    Ok(size) => {
+                        self.file_size = size;
+                        self.file_modified = false;
+                        self.save_error = None;
+                        self.failed_save = None;
+                    }
+                    // 毎フレーム保存を試みるので、同じエラーは一度だけ出力する
+                    Err(err) => {
+                        if self.save_error.as_ref() != Some(&err) {
+                            eprintln!("Failed to save file: {}", err);
+                        }
+                        self.save_error = Some(err);
+                        self.failed_save = Some(attempt);
+                    }
+                }
+            }
+        }
+        !self.file_modified
+    }
+
+    // 保存できなかった変更を捨てずに、どうするかを確認してから続ける
+    fn confirm_unsaved(&mut self, action: PendingOpen) -> bool {
+        if self.save_file_if_modified() {
+            return true;
+        }
+        self.unsaved_prompt = Some(action);
+        false
+    }
+
+    // 確認の後で、保留していた操作をやり直す
+    pub fn resume_pending_open(&mut self, action: PendingOpen) {
+        match action {
+            PendingOpen::File(path) => self.open_file(&path),
+            PendingOpen::Encoding(encoding) => self.reopen_with_encoding(encoding),
+            PendingOpen::Folder(path) => self.open_folder(path),
+        }
+    }
+
+    pub fn open_folder(&mut self, path: PathBuf) {
+        if !self.confirm_unsaved(PendingOpen::Folder(path.clone())) {
+            return;
+        }
+        self.save_workspace_state();
+        self.folder_path = Some(path.clone());
+        self.selected_item = Some(path.clone());
+        self.selected_items.clear();
+        self.expanded_dirs.clear();
+        self.page_limits.clear();
+        self.clear_document();
+        self.tags = TagStore::load(&path);
+        self.restore_workspace_state(&path);
+        self.refresh_file_list();
+    }
+
+    // ワークスペースの状態を集める
+    fn workspace_state(&self, root: &Path) -> WorkspaceState {
+        let key = |path: &PathBuf| relative_key(root, path);
+        let mut expanded: Vec<String> = self.expanded_dirs.iter().filter_map(key).collect();
+        expanded.sort();
+
+        let options = &self.tree_options;
+        let mut settings = vec![
+            ("sort".to_string(), options.sort_mode.label().to_string()),
+            (
+                "folders_first".to_string(),
+                options.folders_first.to_string(),
+            ),
+            ("show_hidden".to_string(), options.show_hidden.to_string()),
+            (
+                "exclude_markup".to_string(),
+                self.exclude_markup.to_string(),
+            ),
+        ];
+        let rules = &self.indent_rules;
+        settings.extend([
+            ("auto_indent".to_string(), rules.auto_indent.to_string()),
+            ("auto_pair".to_string(), rules.auto_pair.to_string()),
+            ("indent_exempt".to_string(), rules.exempt.clone()),
+            (
+                "show_whitespace".to_string(),
+                self.show_whitespace.to_string(),
+            ),
+            (
+                "highlight_whitespace".to_string(),
+                self.highlight_whitespace.to_string(),
+            ),
+            (
+                "line_numbers".to_string(),
+                self.show_line_numbers.to_string(),
+            ),
+            (
+                "visual_line_numbers".to_string(),
+                self.visual_line_numbers.to_string(),
+            ),
+            (
+                "highlight_current_line".to_string(),
+                self.highlight_current_line.to_string(),
+            ),
+        ]);
+        settings.push(("wrap_columns".to_string(), self.wrap_columns.to_string()));
+        settings.extend(
+            self.rulers
+                .iter()
+                .map(|column| ("ruler".to_string(), column.to_string())),
+        );
+        settings.push((
+            "lint_sentence_length".to_string(),
+            self.lint_config.max_sentence_length.to_string(),
+        ));
+        settings.extend(
+            self.lint_config
+                .disabled
+                .iter()
+                .map(|rule| ("lint_disabled".to_string(), rule.key().to_string())),
+        );
+        settings.extend(
+            options
+                .hidden_extensions
+                .iter()
+                .map(|extension| ("hidden_extension".to_string(), extension.clone())),
+        );
+
+        WorkspaceState {
+            expanded,
+            selected: self.selected_items.iter().filter_map(key).collect(),
+            active: self.selected_item.as_ref().and_then(key),
+            open_file: self.selected_file.as_ref().and_then(key),
+            scroll: self.tree_scroll_offset,
+            settings,
+        }
+    }
+
+    // 前回保存時から変わっていれば保存する
+    pub fn save_workspace_state(&mut self) {
+        let Some(root_dir) = &self.folder_path else {
+            return;
+        };
+        let state = self.workspace_state(root_dir);
+        let serialized = state.serialize();
+        if serialized == self.saved_workspace_state {
+            return;
+        }
+        match state.save(root_dir) {
+            Ok(()) => self.saved_workspace_state = serialized,
+            Err(err) => eprintln!("Failed to save workspace state: {}", err),
+        }
+    }
+
+    fn restore_workspace_state(&mut self, root: &Path) {
+        let state = WorkspaceState::load(root);
+        let resolve = |key: &String| resolve_key(root, key);
+
+        self.expanded_dirs = state.expanded.iter().map(resolve).collect();
+        self.selected_items = state
+            .selected
+            .iter()
+            .map(resolve)
+            .filter(|path| path.exists())
+            .collect();
+        if let Some(active) = state.active.as_ref().map(resolve) {
+            self.selected_item = Some(active);
+        }
+        self.restore_tree_scroll = Some(state.scroll);
+
+        self.exclude_markup = state.setting("exclude_markup") == Some("true");
+        let mut rules = IndentRules::default();
+        if let Some(value) = state.setting("auto_indent") {
+            rules.auto_indent = value == "true";
+        }
+        if let Some(value) = state.setting("auto_pair") {
+            rules.auto_pair = value == "true";
+        }
+        if let Some(exempt) = state.setting("indent_exempt") {
+            rules.exempt = exempt.to_string();
+        }
+        self.indent_rules = rules;
+        self.show_whitespace = state.setting("show_whitespace") == Some("true");
+        self.highlight_whitespace = state.setting("highlight_whitespace") == Some("true");
+        // 行番号と現在行の強調は既定で表示する
+        self.show_line_numbers = state.setting("line_numbers") != Some("false");
+        self.visual_line_numbers = state.setting("visual_line_numbers") == Some("true");
+        self.highlight_current_line = state.setting("highlight_current_line") != Some("false");
+        self.wrap_columns = state
+            .setting("wrap_columns")
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(0);
+        self.rulers = state
+            .settings_for("ruler")
+            .filter_map(|column| column.parse().ok())
+            .collect();
+        self.rulers_input = format_rulers(&self.rulers);
+        self.lint_config = LintConfig::default();
+        if let Some(length) = state
+            .setting("lint_sentence_length")
+            .and_then(|length| length.parse().ok())
+        {
+            self.lint_config.max_sentence_length = length;
+        }
+        self.lint_config.disabled = state
+            .settings_for("lint_disabled")
+            .filter_map(Rule::from_key)
+            .collect();
+        self.lint_revision = None;
+        let options = &mut self.tree_options;
+        if let Some(mode) = state.setting("sort") {
+            options.sort_mode = SortMode::ALL
+                .into_iter()
+                .find(|m| m.label() == mode)
+                .unwrap_or(SortMode::Name);
+        }
+        options.folders_first = state.setting("folders_first") != Some("false");
+        options.show_hidden = state.setting("show_hidden") == Some("true");
+        options.hidden_extensions = state
+            .settings_for("hidden_extension")
+            .map(str::to_string)
+            .collect();
+
+        if let Some(open_file) = state.open_file.as_ref().map(resolve) {
+            if open_file.is_file() {
+                let selected_items = self.selected_items.clone();
+                let selected_item = self.selected_item.clone();
+                self.open_file(&open_file);
+                self.selected_items = selected_items;
+                self.selected_item = selected_item;
+            }
+        }
+        self.saved_workspace_state = state.serialize();
+    }
+
+    // 開いているファイルまでフォルダを展開して選択する
+    pub fn reveal_active_file(&mut self) {
+        let (Some(root_dir), Some(active_file)) = (&self.folder_path, &self.selected_file) else {
+            return;
+        };
+        if !matches_filter(
+            active_file,
+            root_dir,
+            &self.tree_options,
+            &self.ignore_rules,
+        ) {
+            self.tree_options.filter.clear();
+        }
+        for ancestor in active_file.ancestors().skip(1) {
+            if !ancestor.starts_with(root_dir) || ancestor == root_dir {
+                break;
+            }
+            self.expanded_dirs.insert(ancestor.to_path_buf());
+        }
+        self.selected_item = Some(active_file.clone());
+        self.selected_items = vec![active_file.clone()];
+        self.scroll_to_selected = true;
+        self.refresh_file_list();
+    }
+
+    // ファイルを開いて中央のエディタに表示
+    pub fn open_file(&mut self, path: &Path) {
+        if !self.confirm_unsaved(PendingOpen::File(path.to_path_buf())) {
+            return;
+        }
+        self.selected_item = Some(path.to_path_buf());
+        self.selected_items = vec![path.to_path_buf()];
+        self.selected_file = Some(path.to_path_buf());
+        match document::load(path) {
+            Ok(loaded) => self.set_document(loaded),
+            Err(_) => {
+                self.file_contents = "Failed to read file".to_string();
+                self.stats = DocumentStats::new(&self.file_contents);
+                self.text_revision += 1;
+            }
+        }
+        self.file_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        self.file_modified = false;
+        self.save_error = None;
+        self.editor_mode = editor_mode_for(path);
+        self.pending_selection = Some(0..0);
+    }
+
+    // 開いている文書を閉じる（統計や版で作り直す表示もすべて空にする）
+    pub fn clear_document(&mut self) {
+        self.selected_file = None;
+        self.file_contents.clear();
+        self.file_modified = false;
+        self.stats = DocumentStats::new("");
+        self.text_revision += 1;
+    }
+
+    fn set_document(&mut self, loaded: document::Document) {
+        self.file_contents = loaded.text;
+        self.encoding = loaded.encoding;
+        self.line_ending = loaded.line_ending;
+        self.stats = DocumentStats::new(&self.file_contents);
+        self.text_revision += 1;
+    }
+
+    // 開いているファイルを別の文字コードとして読み直す
+    pub fn reopen_with_encoding(&mut self, encoding: TextEncoding) {
+        if !self.confirm_unsaved(PendingOpen::Encoding(encoding)) {
+            return;
+        }
+        let Some(path) = self.selected_file.clone() else {
+            return;
+        };
+        match document::load_with(&path, encoding) {
+            Ok(loaded) => self.set_document(loaded),
+            Err(err) => eprintln!("Failed to read file: {}", err),
+        }
+    }
+
+    // 文字位置の範囲を置き換えて、置き換えた部分を選択する
+    // 置き換えの前後を取り消しの区切りにして、一度の取り消しで元に戻せるようにする
+    pub fn replace_range(&mut self, ctx: &Context, char_range: Range<usize>, replacement: &str) {
+        let editor_id = Id::new(EDITOR_ID);
+        let mut state = TextEdit::load_state(ctx, editor_id).unwrap_or_default();
+        let mut undoer = state.undoer();
+        let cursor = state.cursor.char_range().unwrap_or(CCursorRange::two(
+            CCursor::new(char_range.start),
+            CCursor::new(char_range.end),
+        ));
+        undoer.add_undo(&(cursor, self.file_contents.clone()));
+
+        let mut buffer = TrackedText {
+            text: &mut self.file_contents,
+            stats: &mut self.stats,
+        };
+        buffer.delete_char_range(char_range.clone());
+        let inserted = buffer.insert_text(replacement, char_range.start);
+        let selection = char_range.start..char_range.start + inserted;
+
+        let cursor = CCursorRange::two(CCursor::new(selection.start), CCursor::new(selection.end));
+        undoer.add_undo(&(cursor, self.file_contents.clone()));
+        state.set_undoer(undoer);
+        TextEdit::store_state(ctx, editor_id, state);
+
+        self.pending_selection = Some(selection);
+        self.file_modified = true;
+        self.text_revision += 1;
+    }
+
+    // 指定した行の先頭へカーソルを移動する（行番号は 1 から）
+    pub fn go_to_line(&mut self, line: usize) {
+        let start = self
+            .file_contents
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(|line| line.chars().count())
+            .sum();
+        self.pending_selection = Some(start..start);
+        self.focus_editor = true;
+    }
+
+    // 文書か校正の設定が変わったときだけ校正し直す
+    // 入力中は、入力が止まってから LINT_DELAY 秒後に校正する
+    fn update_diagnostics(&mut self, ctx: &Context) {
+        if self.selected_file.is_none() {
+            if !self.diagnostics.is_empty() {
+                self.diagnostics.clear();
+                self.diagnostics_revision += 1;
+            }
+            return;
+        }
+        let now = ctx.input(|i| i.time);
+        if self.last_edit.0 != self.text_revision {
+            self.last_edit = (self.text_revision, now);
+        }
+        if self.lint_revision.is_some() && self.lint_revision != Some(self.text_revision) {
+            let waited = now - self.last_edit.1;
+            if waited < LINT_DELAY {
+                ctx.request_repaint_after(Duration::from_secs_f64(LINT_DELAY - waited));
+                return;
+            }
+        }
+        if self.lint_revision != Some(self.text_revision) {
+            self.diagnostics = lint(&self.file_contents, &self.lint_config);
+            self.diagnostics_revision += 1;
+            self.lint_revision = Some(self.text_revision);
+        }
+    }
+
+    pub fn refresh_file_list(&mut self) {
+        if let Some(root_dir) = self.folder_path.clone() {
+            self.ignore_rules = IgnoreRules::load(&root_dir);
+            self.children_cache.clear();
+            self.file_list = self.cached_children(&root_dir).to_vec();
+        }
+    }
+
+    // 一度読み込んだフォルダの内容は再読み込みまで使い回す
+    pub fn cached_children(&mut self, dir: &Path) -> &[PathBuf] {
+        &self.cached_dir(dir).children
+    }
+
+    pub fn cached_dir(&mut self, dir: &Path) -> &CachedDir {
+        if !self.children_cache.contains_key(dir) {
+            let cached = CachedDir {
+                modified: dir_modified(dir),
+                canonical: fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()),
+                children: self.visible_children(dir),
+            };
+            self.children_cache.insert(dir.to_path_buf(), cached);
+        }
+        &self.children_cache[dir]
+    }
+
+    // 更新日時が変わったフォルダの一覧を捨てる
+    // 更新日時・サイズ順ではファイルの保存でも順序が変わるので、開いているファイルのフォルダも読み直す
+    fn invalidate_changed_dirs(&mut self) {
+        self.children_cache
+            .retain(|dir, cached| dir_modified(dir) == cached.modified);
+        if matches!(
+            self.tree_options.sort_mode,
+            SortMode::Modified | SortMode::Size
+        ) {
+            if let Some(parent) = self.selected_file.as_ref().and_then(|file| file.parent()) {
+                self.children_cache.remove(parent);
+            }
+        }
+    }
+
+    // 表示設定・無視パターン・絞り込みを適用したフォルダ内の項目
+    pub fn visible_children(&self, dir: &Path) -> Vec<PathBuf> {
+        let Some(root_dir) = &self.folder_path else {
+            return Vec::new();
+        };
+        list_children(dir, &self.tree_options)
+            .into_iter()
+            .filter(|path| {
+                is_visible(path, root_dir, &self.tree_options, &self.ignore_rules)
+                    && matches_filter(path, root_dir, &self.tree_options, &self.ignore_rules)
+            })
+            .collect()
+    }
+
+    // 移動・名前変更されたパスを開いているファイルや選択に反映する
+    pub fn path_moved(&mut self, from: &Path, to: &Path) {
+        self.paths_moved(&[(from.to_path_buf(), to.to_path_buf())]);
+    }
+
+    // まとめて名前を変えた・移動した項目を反映する（手動の並び順は一度に書き換える）
+    pub fn paths_moved(&mut self, moved: &[(PathBuf, PathBuf)]) {
+        if let Err(err) = rename_in_manual_order(moved) {
+            eprintln!("Failed to update the manual order: {}", err);
+        }
+        for (from, to) in moved {
+            self.relocate_path(from, to);
+        }
+        if let Err(err) = self.tags.save() {
+            eprintln!("Failed to save tags: {}", err);
+        }
+    }
+
+    fn relocate_path(&mut self, from: &Path, to: &Path) {
+        let relocate = |path: &mut Option<PathBuf>| {
+            if let Some(current) = path {
+                if let Ok(rest) = current.strip_prefix(from) {
+                    *current = to.join(rest);
                 }
             }
+        };
+        relocate(&mut self.selected_file);
+        if let Some(selected_file) = &self.selected_file {
+            self.editor_mode = editor_mode_for(selected_file);
+        }
+        relocate(&mut self.selected_item);
+        relocate(&mut self.right_panel_file);
+        for item in &mut self.selected_items {
+            if let Ok(rest) = item.strip_prefix(from) {
+                *item = to.join(rest);
+            }
+        }
+        self.expanded_dirs = self
+            .expanded_dirs
+            .drain()
+            .map(|dir| match dir.strip_prefix(from) {
+                Ok(rest) => to.join(rest),
+                Err(_) => dir,
+            })
+            .collect();
+
+        self.tags.path_moved(from, to);
+    }
+
+    // ファイル・フォルダをまとめてゴミ箱へ移動
+    pub fn delete_items(&mut self, paths: &[PathBuf]) {
+        for path in paths {
+            if let Err(err) = move_to_trash(path) {
+                eprintln!("Failed to move file to trash: {}", err);
+                continue;
+            }
+
+            if self
+                .selected_file
+                .as_ref()
+                .is_some_and(|file| file.starts_with(path))
+            {
+                self.clear_document();
+            }
+            if self
+                .selected_item
+                .as_ref()
+                .is_some_and(|item| item.starts_with(path))
+            {
+                self.selected_item = None;
+            }
+            self.selected_items.retain(|item| !item.starts_with(path));
+            self.tags.path_removed(path);
+        }
+
+        if let Err(err) = self.tags.save() {
+            eprintln!("Failed to save tags: {}", err);
+        }
+        self.refresh_file_list();
+    }
+
+    pub fn queue_transfer(&mut self, source: PathBuf, target_dir: PathBuf, mode: ClipboardMode) {
+        self.transfer_queue.push_back(TransferRequest {
+            source,
+            target_dir,
+            mode,
+        });
+    }
+
+    // 順番待ちのコピー・移動を一件ずつ開始する
+    fn process_transfer_queue(&mut self) {
+        if self.file_transfer.is_some() || self.paste_conflict.is_some() {
+            return;
+        }
+        let Some(request) = self.transfer_queue.pop_front() else {
+            return;
+        };
+
+        match paste_destination(&request.source, &request.target_dir, request.mode) {
+            Ok(Some(destination)) => {
+                self.file_transfer = Some(FileTransfer::start(
+                    request.source,
+                    destination,
+                    request.mode,
+                    false,
+                ));
+            }
+            Ok(None) => {
+                self.paste_conflict = Some(PendingPaste {
+                    destination: request.target_dir.join(request.source.file_name().unwrap()),
+                    source: request.source,
+                    mode: request.mode,
+                });
+            }
+            Err(err) => eprintln!("Failed to paste: {}", err),
+        }
+    }
+
+    pub fn resolve_paste_conflict(&mut self, resolution: ConflictResolution) {
+        let Some(pending) = self.paste_conflict.take() else {
+            return;
+        };
+        match resolve_conflict(&pending, resolution) {
+            Ok(Some(destination)) => {
+                self.file_transfer = Some(FileTransfer::start(
+                    pending.source,
+                    destination,
+                    pending.mode,
+                    resolution == ConflictResolution::Overwrite,
+                ));
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("Failed to paste: {}", err),
+        }
+    }
+
+    // バックグラウンドのコピー・移動の進捗確認
+    pub fn poll_file_transfer(&mut self, ctx: &Context) {
+        self.process_transfer_queue();
+        let Some(transfer) = &mut self.file_transfer else {
+            return;
+        };
+
+        match transfer.poll() {
+            TransferStatus::Running => ctx.request_repaint(),
+            TransferStatus::Finished(result) => {
+                let transfer = self.file_transfer.take().unwrap();
+                match result {
+                    Ok(()) => {
+                        if transfer.mode == ClipboardMode::Cut {
+                            self.path_moved(&transfer.source, &transfer.destination);
+                        }
+                    }
+                    Err(err) => eprintln!("{}", err),
+                }
+                self.refresh_file_list();
+            }
         }
     }
 }
@@ -121,10 +926,36 @@ impl App for TxtEditorApp {
         }
 
         self.save_file_if_modified();
+        self.poll_file_transfer(ctx);
+
+        let now = ctx.input(|i| i.time);
+        if now - self.last_state_save >= 2.0 {
+            self.save_workspace_state();
+            self.last_state_save = now;
+            // 外部での変更を反映するため、変更されたフォルダだけを読み直す
+            self.invalidate_changed_dirs();
+        }
+
+        self.update_diagnostics(ctx);
 
         crate::ui::display_top_panel(self, ctx);
+        crate::ui::display_bottom_panel(self, ctx);
+        if self.show_problems {
+            crate::ui::display_problems(self, ctx);
+        }
         crate::ui::display_left_panel(self, ctx);
         crate::ui::display_right_panel(self, ctx);
+        if self.vertical_preview {
+            crate::ui::display_vertical_preview(self, ctx);
+        }
         crate::ui::display_central_panel(self, ctx);
+        if self.unsaved_prompt.is_some() {
+            crate::ui::display_unsaved_prompt(self, ctx);
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_file_if_modified();
+        self.save_workspace_state();
     }
 }
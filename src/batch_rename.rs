@@ -0,0 +1,380 @@
+use crate::file_operations::{temporary_path, unique_destination};
+use crate::text::convert::{to_full_width_alnum, to_half_width_alnum};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CaseConversion {
+    None,
+    Upper,
+    Lower,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WidthConversion {
+    None,
+    ToFullWidth,
+    ToHalfWidth,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct BatchRenameOptions {
+    pub find: String,
+    pub replace: String,
+    pub use_regex: bool,
+    pub numbering: bool,
+    pub start: u32,
+    pub step: u32,
+    pub padding: usize,
+    pub separator: String,
+    pub strip_existing_number: bool,
+    pub case: CaseConversion,
+    pub width: WidthConversion,
+}
+
+impl Default for BatchRenameOptions {
+    fn default() -> Self {
+        Self {
+            find: String::new(),
+            replace: String::new(),
+            use_regex: false,
+            numbering: false,
+            start: 1,
+            step: 1,
+            padding: 2,
+            separator: "_".to_string(),
+            strip_existing_number: true,
+            case: CaseConversion::None,
+            width: WidthConversion::None,
+        }
+    }
+}
+
+pub struct RenamePreview {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub conflict: Option<String>,
+}
+
+impl RenamePreview {
+    pub fn is_changed(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+// 一括リネームのプレビュー（拡張子は変更しない）
+pub fn preview(
+    targets: &[PathBuf],
+    options: &BatchRenameOptions,
+) -> Result<Vec<RenamePreview>, String> {
+    let pattern = if options.use_regex && !options.find.is_empty() {
+        Some(Regex::new(&options.find).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
+    let mut previews: Vec<RenamePreview> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let (stem, extension) = split_name(path);
+            let mut name = stem;
+
+            if options.numbering && options.strip_existing_number {
+                name = strip_leading_number(&name);
+            }
+            if let Some(pattern) = &pattern {
+                name = pattern
+                    .replace_all(&name, options.replace.as_str())
+                    .to_string();
+            } else if !options.find.is_empty() {
+                name = name.replace(&options.find, &options.replace);
+            }
+            name = match options.case {
+                CaseConversion::None => name,
+                CaseConversion::Upper => name.to_uppercase(),
+                CaseConversion::Lower => name.to_lowercase(),
+            };
+            name = match options.width {
+                WidthConversion::None => name,
+                WidthConversion::ToFullWidth => to_full_width_alnum(&name),
+                WidthConversion::ToHalfWidth => to_half_width_alnum(&name),
+            };
+            if options.numbering {
+                let number = options.start as usize + index * options.step as usize;
+                name = format!(
+                    "{:0width$}{}{}",
+                    number,
+                    options.separator,
+                    name,
+                    width = options.padding
+                );
+            }
+
+            RenamePreview {
+                from: path.clone(),
+                to: path.with_file_name(format!("{}{}", name, extension)),
+                conflict: None,
+            }
+        })
+        .collect();
+
+    check_conflicts(&mut previews);
+    Ok(previews)
+}
+
+// 名前と拡張子（"." を含む）に分ける
+fn split_name(path: &Path) -> (String, String) {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    if path.is_dir() {
+        return (file_name.to_string(), String::new());
+    }
+    match path.extension() {
+        Some(extension) => (
+            path.file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            format!(".{}", extension.to_string_lossy()),
+        ),
+        None => (file_name.to_string(), String::new()),
+    }
+}
+
+// 先頭の番号と区切り文字（"01_" "2-" "03 " など）を取り除く
+fn strip_leading_number(name: &str) -> String {
+    let rest = name.trim_start_matches(|c: char| c.is_ascii_digit() || ('０'..='９').contains(&c));
+    if rest.len() == name.len() {
+        return name.to_string();
+    }
+    rest.trim_start_matches(['_', '-', ' ', '　', '.'])
+        .to_string()
+}
+
+// 変更後の名前の重複・既存ファイルとの衝突を調べる
+fn check_conflicts(previews: &mut [RenamePreview]) {
+    let sources: HashSet<PathBuf> = previews.iter().map(|p| p.from.clone()).collect();
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for preview in previews.iter() {
+        *counts.entry(preview.to.clone()).or_default() += 1;
+    }
+
+    for preview in previews.iter_mut() {
+        let name = preview.to.file_name().unwrap_or_default().to_string_lossy();
+        preview.conflict = if name.is_empty() || name.starts_with('.') {
+            Some("Name is empty".to_string())
+        } else if name.contains(['/', '\\']) {
+            Some("Name contains a path separator".to_string())
+        } else if counts[&preview.to] > 1 {
+            Some("Duplicate name".to_string())
+        } else if preview.is_changed() && preview.to.exists() && !sources.contains(&preview.to) {
+            Some("A file with this name already exists".to_string())
+        } else {
+            None
+        };
+    }
+}
+
+// 変更前と変更後のパスの組
+pub type Renamed = Vec<(PathBuf, PathBuf)>;
+
+// 一時的な名前を経由して名前を変更する（入れ替えにも対応）
+// 途中で失敗した場合は、変更済みの組と失敗の内容を返す
+pub fn apply(previews: &[RenamePreview]) -> Result<Renamed, (Renamed, String)> {
+    if let Some(preview) = previews.iter().find(|p| p.conflict.is_some()) {
+        return Err((
+            Vec::new(),
+            format!(
+                "{}: {}",
+                preview.to.display(),
+                preview.conflict.as_ref().unwrap()
+            ),
+        ));
+    }
+
+    let changed: Vec<&RenamePreview> = previews.iter().filter(|p| p.is_changed()).collect();
+    let mut temporary = Vec::new();
+    for preview in &changed {
+        let dir = preview.from.parent().unwrap_or(Path::new(""));
+        let file_name = preview
+            .from
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let temp_path = temporary_path(dir, &file_name, "batch-rename");
+        if let Err(err) = fs::rename(&preview.from, &temp_path) {
+            // 途中で失敗したら元に戻す
+            for (original, temp) in temporary.iter().rev() {
+                let _ = fs::rename(temp, original);
+            }
+            return Err((
+                Vec::new(),
+                format!("Failed to rename {}: {}", preview.from.display(), err),
+            ));
+        }
+        temporary.push((preview.from.clone(), temp_path));
+    }
+
+    let mut renamed = Vec::new();
+    for (index, preview) in changed.iter().enumerate() {
+        let temp_path = &temporary[index].1;
+        if let Err(err) = fs::rename(temp_path, &preview.to) {
+            let error = format!("Failed to rename {}: {}", preview.from.display(), err);
+            // 残りは元の名前に戻す（元の名前が使われていれば重複しない名前にする）
+            for (original, temp) in &temporary[index..] {
+                let mut restored = original.clone();
+                if restored.exists() {
+                    let dir = original.parent().unwrap_or(Path::new(""));
+                    let file_name = original.file_name().unwrap_or_default().to_string_lossy();
+                    restored = unique_destination(dir, &file_name);
+                }
+                match fs::rename(temp, &restored) {
+                    Ok(()) if restored != *original => renamed.push((original.clone(), restored)),
+                    Ok(()) => {}
+                    Err(err) => eprintln!("Failed to restore {}: {}", original.display(), err),
+                }
+            }
+            return Err((renamed, error));
+        }
+        renamed.push((preview.from.clone(), preview.to.clone()));
+    }
+    Ok(renamed)
+}
+
+// 一括リネームダイアログの状態
+pub struct BatchRenameDialog {
+    pub targets: Vec<PathBuf>,
+    pub options: BatchRenameOptions,
+    // 最後にプレビューしたときの設定と結果（設定が変わるまで作り直さない）
+    previews: Option<(BatchRenameOptions, Result<Vec<RenamePreview>, String>)>,
+}
+
+impl BatchRenameDialog {
+    pub fn new(targets: Vec<PathBuf>) -> Self {
+        Self {
+            targets,
+            options: BatchRenameOptions::default(),
+            previews: None,
+        }
+    }
+
+    pub fn previews(&mut self) -> &Result<Vec<RenamePreview>, String> {
+        let stale = !matches!(&self.previews, Some((options, _)) if *options == self.options);
+        if stale {
+            let previews = preview(&self.targets, &self.options);
+            self.previews = Some((self.options.clone(), previews));
+        }
+        &self.previews.as_ref().unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(previews: &[RenamePreview]) -> Vec<String> {
+        previews
+            .iter()
+            .map(|preview| {
+                preview
+                    .to
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn numbers_and_replaces_names() {
+        let dir = Path::new("/nonexistent-batch-rename");
+        let targets = ["03_序章.txt", "10-本編.txt", "あとがき"].map(|name| dir.join(name));
+        let options = BatchRenameOptions {
+            find: "章".to_string(),
+            replace: "話".to_string(),
+            numbering: true,
+            start: 1,
+            step: 10,
+            ..Default::default()
+        };
+        let previews = preview(&targets, &options).unwrap();
+        assert_eq!(
+            names(&previews),
+            ["01_序話.txt", "11_本編.txt", "21_あとがき"]
+        );
+        assert!(previews.iter().all(|preview| preview.conflict.is_none()));
+    }
+
+    #[test]
+    fn converts_case_and_width_but_not_extension() {
+        let targets = [PathBuf::from("/nonexistent-batch-rename/Draft１.TXT")];
+        let options = BatchRenameOptions {
+            case: CaseConversion::Lower,
+            width: WidthConversion::ToHalfWidth,
+            ..Default::default()
+        };
+        let previews = preview(&targets, &options).unwrap();
+        assert_eq!(names(&previews), ["draft1.TXT"]);
+
+        let options = BatchRenameOptions {
+            find: "(".to_string(),
+            use_regex: true,
+            ..Default::default()
+        };
+        assert!(preview(&targets, &options).is_err());
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        let dir = std::env::temp_dir().join(format!("txt-editor-rename-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["a.txt", "b.txt", "taken.txt"] {
+            fs::write(dir.join(name), name).unwrap();
+        }
+
+        // 既存のファイルとの衝突
+        let targets = [dir.join("a.txt")];
+        let options = BatchRenameOptions {
+            find: "a".to_string(),
+            replace: "taken".to_string(),
+            ..Default::default()
+        };
+        let previews = preview(&targets, &options).unwrap();
+        assert!(previews[0].conflict.is_some());
+
+        // 同じ名前になる組と、空の名前
+        let targets = [dir.join("a.txt"), dir.join("b.txt")];
+        let options = BatchRenameOptions {
+            find: "[ab]".to_string(),
+            replace: "c".to_string(),
+            use_regex: true,
+            ..Default::default()
+        };
+        let previews = preview(&targets, &options).unwrap();
+        assert!(previews.iter().all(|preview| preview.conflict.is_some()));
+        let options = BatchRenameOptions {
+            find: "a".to_string(),
+            ..Default::default()
+        };
+        assert!(preview(&targets[..1], &options).unwrap()[0]
+            .conflict
+            .is_some());
+
+        // 入れ替えは選んだ項目どうしなので衝突しない
+        let targets = [dir.join("a.txt"), dir.join("b.txt")];
+        let mut previews = preview(&targets, &BatchRenameOptions::default()).unwrap();
+        previews[0].to = dir.join("b.txt");
+        previews[1].to = dir.join("a.txt");
+        check_conflicts(&mut previews);
+        assert!(previews.iter().all(|preview| preview.conflict.is_none()));
+        apply(&previews).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "b.txt");
+        assert_eq!(fs::read_to_string(dir.join("b.txt")).unwrap(), "a.txt");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,271 @@
+use crate::file_operations::{
+    copy_recursive, count_files, move_to_trash, remove_path, temporary_path, unique_destination,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClipboardMode {
+    Copy,
+    Cut,
+}
+
+// ツリー内のクリップボード
+pub struct TreeClipboard {
+    pub paths: Vec<PathBuf>,
+    pub mode: ClipboardMode,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConflictResolution {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+// 順番待ちのコピー・移動
+pub struct TransferRequest {
+    pub source: PathBuf,
+    pub target_dir: PathBuf,
+    pub mode: ClipboardMode,
+}
+
+// 貼り付け先に同名の項目がある場合の保留中の操作
+pub struct PendingPaste {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub mode: ClipboardMode,
+}
+
+enum TransferMessage {
+    Total(u64),
+    Progress,
+    Finished(Result<(), String>),
+}
+
+// バックグラウンドで実行中のコピー・移動
+pub struct FileTransfer {
+    pub label: String,
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub mode: ClipboardMode,
+    pub done: u64,
+    // 数え終わるまでは None
+    pub total: Option<u64>,
+    receiver: Receiver<TransferMessage>,
+}
+
+pub enum TransferStatus {
+    Running,
+    Finished(Result<(), String>),
+}
+
+impl FileTransfer {
+    // overwrite なら、コピーし終えてから既存の項目をゴミ箱へ移して置き換える
+    pub fn start(
+        source: PathBuf,
+        destination: PathBuf,
+        mode: ClipboardMode,
+        overwrite: bool,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let label = format!(
+            "{} {}",
+            if mode == ClipboardMode::Cut {
+                "Moving"
+            } else {
+                "Copying"
+            },
+            source.file_name().unwrap_or_default().to_string_lossy()
+        );
+
+        let (thread_source, thread_destination) = (source.clone(), destination.clone());
+        thread::spawn(move || {
+            // 大きなフォルダでも画面を止めないよう、ファイル数はこのスレッドで数える
+            let _ = sender.send(TransferMessage::Total(count_files(&thread_source)));
+            let mut on_file_copied = || {
+                let _ = sender.send(TransferMessage::Progress);
+            };
+            let result = if overwrite {
+                transfer_overwriting(
+                    &thread_source,
+                    &thread_destination,
+                    mode,
+                    &mut on_file_copied,
+                )
+            } else {
+                transfer(
+                    &thread_source,
+                    &thread_destination,
+                    mode,
+                    &mut on_file_copied,
+                )
+            };
+            let _ = sender.send(TransferMessage::Finished(result));
+        });
+
+        Self {
+            label,
+            source,
+            destination,
+            mode,
+            done: 0,
+            total: None,
+            receiver,
+        }
+    }
+
+    pub fn progress(&self) -> f32 {
+        match self.total {
+            None => 0.0,
+            Some(0) => 1.0,
+            Some(total) => self.done as f32 / total as f32,
+        }
+    }
+
+    pub fn poll(&mut self) -> TransferStatus {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(TransferMessage::Total(total)) => self.total = Some(total),
+                Ok(TransferMessage::Progress) => self.done += 1,
+                Ok(TransferMessage::Finished(result)) => return TransferStatus::Finished(result),
+                Err(TryRecvError::Empty) => return TransferStatus::Running,
+                Err(TryRecvError::Disconnected) => {
+                    return TransferStatus::Finished(Err("Transfer thread stopped".to_string()))
+                }
+            }
+        }
+    }
+}
+
+fn transfer(
+    source: &Path,
+    destination: &Path,
+    mode: ClipboardMode,
+    on_file_copied: &mut dyn FnMut(),
+) -> Result<(), String> {
+    if mode == ClipboardMode::Cut && fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+
+    // 別ドライブへの移動はコピー後に元を削除する
+    copy_recursive(source, destination, on_file_copied)
+        .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+    if mode == ClipboardMode::Cut {
+        remove_path(source).map_err(|e| format!("Failed to remove {}: {}", source.display(), e))?;
+    }
+    Ok(())
+}
+
+// 同名の項目を置き換える
+// 一時的な名前にコピーし終えてから、既存の項目をゴミ箱へ移して入れ替える
+fn transfer_overwriting(
+    source: &Path,
+    destination: &Path,
+    mode: ClipboardMode,
+    on_file_copied: &mut dyn FnMut(),
+) -> Result<(), String> {
+    let dir = destination.parent().unwrap_or(Path::new(""));
+    let file_name = destination
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let temporary = temporary_path(dir, &file_name, "paste");
+
+    let moved = mode == ClipboardMode::Cut && fs::rename(source, &temporary).is_ok();
+    if !moved {
+        if let Err(e) = copy_recursive(source, &temporary, on_file_copied) {
+            let _ = remove_path(&temporary);
+            return Err(format!("Failed to copy {}: {}", source.display(), e));
+        }
+    }
+
+    // 既存の項目をゴミ箱へ移せなければ、貼り付けを取りやめて元に戻す
+    let trashed = move_to_trash(&destination.to_path_buf()).and_then(|()| {
+        if destination.exists() {
+            Err(format!("Failed to move {} to trash", destination.display()))
+        } else {
+            Ok(())
+        }
+    });
+    if let Err(err) = trashed {
+        let _ = if moved {
+            fs::rename(&temporary, source)
+        } else {
+            remove_path(&temporary)
+        };
+        return Err(err);
+    }
+    fs::rename(&temporary, destination).map_err(|e| {
+        format!(
+            "Failed to rename {} to {}: {}",
+            temporary.display(),
+            destination.display(),
+            e
+        )
+    })?;
+
+    if mode == ClipboardMode::Cut && !moved {
+        remove_path(source).map_err(|e| format!("Failed to remove {}: {}", source.display(), e))?;
+    }
+    Ok(())
+}
+
+// 貼り付け先のパスを決める。同名の項目がある場合は None
+pub fn paste_destination(
+    source: &Path,
+    target_dir: &Path,
+    mode: ClipboardMode,
+) -> Result<Option<PathBuf>, String> {
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| "Invalid source path".to_string())?;
+    if target_dir.starts_with(source) {
+        return Err("Cannot paste a folder into itself".to_string());
+    }
+
+    let destination = target_dir.join(file_name);
+    if destination == source {
+        // 同じ場所へのコピーは複製として扱う
+        return match mode {
+            ClipboardMode::Copy => Ok(Some(unique_destination(
+                target_dir,
+                &file_name.to_string_lossy(),
+            ))),
+            ClipboardMode::Cut => Err("Source and destination are the same".to_string()),
+        };
+    }
+
+    if destination.exists() {
+        Ok(None)
+    } else {
+        Ok(Some(destination))
+    }
+}
+
+// 競合の解決方法に応じて最終的な貼り付け先を決める
+pub fn resolve_conflict(
+    pending: &PendingPaste,
+    resolution: ConflictResolution,
+) -> Result<Option<PathBuf>, String> {
+    match resolution {
+        ConflictResolution::Skip => Ok(None),
+        ConflictResolution::Overwrite => {
+            // 置き換える項目の中にある項目は貼り付けられない（"p/x/x" を "p" へ）
+            if pending.source.starts_with(&pending.destination) {
+                return Err(format!(
+                    "Cannot overwrite {} with an item inside it",
+                    pending.destination.display()
+                ));
+            }
+            Ok(Some(pending.destination.clone()))
+        }
+        ConflictResolution::KeepBoth => {
+            let dir = pending.destination.parent().unwrap_or(Path::new(""));
+            let file_name = pending.destination.file_name().unwrap_or_default();
+            Ok(Some(unique_destination(dir, &file_name.to_string_lossy())))
+        }
+    }
+}
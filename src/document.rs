@@ -0,0 +1,248 @@
+use encoding_rs::{Encoding, EUC_JP, SHIFT_JIS};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    ShiftJis,
+    EucJp,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 6] = [
+        TextEncoding::Utf8,
+        TextEncoding::Utf8Bom,
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::ShiftJis,
+        TextEncoding::EucJp,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 with BOM",
+            TextEncoding::Utf16Le => "UTF-16 LE",
+            TextEncoding::Utf16Be => "UTF-16 BE",
+            TextEncoding::ShiftJis => "Shift_JIS",
+            TextEncoding::EucJp => "EUC-JP",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    // 最初に見つかった改行の種類（改行がなければ LF）
+    fn detect(text: &str) -> Self {
+        match text.find(['\r', '\n']) {
+            Some(index) if text[index..].starts_with("\r\n") => LineEnding::CrLf,
+            Some(index) if text[index..].starts_with('\r') => LineEnding::Cr,
+            _ => LineEnding::Lf,
+        }
+    }
+}
+
+// 読み込んだテキスト（改行は "\n" に統一）
+pub struct Document {
+    pub text: String,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+pub fn load(path: &Path) -> io::Result<Document> {
+    let bytes = fs::read(path)?;
+    Ok(decode(&bytes, detect_encoding(&bytes)))
+}
+
+// 文字コードを指定して読み直す
+pub fn load_with(path: &Path, encoding: TextEncoding) -> io::Result<Document> {
+    let bytes = fs::read(path)?;
+    Ok(decode(&bytes, encoding))
+}
+
+// 保存してファイルサイズを返す
+pub fn save(
+    path: &Path,
+    text: &str,
+    encoding: TextEncoding,
+    line_ending: LineEnding,
+) -> Result<u64, String> {
+    let bytes = encode(text, encoding, line_ending)?;
+    fs::write(path, &bytes).map_err(|err| err.to_string())?;
+    Ok(bytes.len() as u64)
+}
+
+// BOM、UTF-8 として正しいか、Shift_JIS・EUC-JP として正しいかの順に判定する
+// EUC-JP のバイト列はほとんどが Shift_JIS としても読める（半角カナになる）ので、
+// 両方で読めるときは半角カナが少ないほうを選ぶ
+pub fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        TextEncoding::Utf8Bom
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        TextEncoding::Utf16Le
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        TextEncoding::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        let half_width_kana = |encoding: &'static Encoding| {
+            encoding
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .map(|text| {
+                    text.chars()
+                        .filter(|c| matches!(c, '\u{FF61}'..='\u{FF9F}'))
+                        .count()
+                })
+        };
+        match (half_width_kana(SHIFT_JIS), half_width_kana(EUC_JP)) {
+            (Some(sjis), Some(euc)) if euc < sjis => TextEncoding::EucJp,
+            (Some(_), _) => TextEncoding::ShiftJis,
+            (None, Some(_)) => TextEncoding::EucJp,
+            (None, None) => TextEncoding::Utf8,
+        }
+    }
+}
+
+pub fn decode(bytes: &[u8], encoding: TextEncoding) -> Document {
+    let text = match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => {
+            let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+            String::from_utf8_lossy(bytes).into_owned()
+        }
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| match encoding {
+                    TextEncoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                    _ => u16::from_be_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            let text = String::from_utf16_lossy(&units);
+            text.strip_prefix('\u{FEFF}')
+                .map(str::to_string)
+                .unwrap_or(text)
+        }
+        TextEncoding::ShiftJis => SHIFT_JIS.decode_without_bom_handling(bytes).0.into_owned(),
+        TextEncoding::EucJp => EUC_JP.decode_without_bom_handling(bytes).0.into_owned(),
+    };
+
+    let line_ending = LineEnding::detect(&text);
+    Document {
+        text: text.replace("\r\n", "\n").replace('\r', "\n"),
+        encoding,
+        line_ending,
+    }
+}
+
+// 変換できない文字があればエラー
+pub fn encode(
+    text: &str,
+    encoding: TextEncoding,
+    line_ending: LineEnding,
+) -> Result<Vec<u8>, String> {
+    let text = match line_ending {
+        LineEnding::Lf => text.to_string(),
+        _ => text.replace('\n', line_ending.as_str()),
+    };
+
+    let bytes = match encoding {
+        TextEncoding::Utf8 => text.into_bytes(),
+        TextEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => std::iter::once(0xFEFF)
+            .chain(text.encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect(),
+        TextEncoding::Utf16Be => std::iter::once(0xFEFF)
+            .chain(text.encode_utf16())
+            .flat_map(u16::to_be_bytes)
+            .collect(),
+        TextEncoding::ShiftJis | TextEncoding::EucJp => {
+            let target = if encoding == TextEncoding::ShiftJis {
+                SHIFT_JIS
+            } else {
+                EUC_JP
+            };
+            let (bytes, _, had_errors) = target.encode(&text);
+            if had_errors {
+                let unmappable = text
+                    .chars()
+                    .find(|c| target.encode(&c.to_string()).2)
+                    .unwrap_or('?');
+                return Err(format!(
+                    "'{}' cannot be encoded in {}",
+                    unmappable,
+                    encoding.label()
+                ));
+            }
+            bytes.into_owned()
+        }
+    };
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "吾輩は猫である。名前はまだ無い。\nどこで生れたかとんと見当がつかぬ。";
+
+    #[test]
+    fn detects_japanese_encodings() {
+        for encoding in TextEncoding::ALL {
+            let bytes = encode(SAMPLE, encoding, LineEnding::Lf).unwrap();
+            assert!(detect_encoding(&bytes) == encoding, "{}", encoding.label());
+        }
+    }
+
+    #[test]
+    fn keeps_half_width_kana_in_shift_jis() {
+        let bytes = encode("ﾃｽﾄです", TextEncoding::ShiftJis, LineEnding::Lf).unwrap();
+        assert!(detect_encoding(&bytes) == TextEncoding::ShiftJis);
+        assert_eq!(decode(&bytes, TextEncoding::ShiftJis).text, "ﾃｽﾄです");
+    }
+
+    #[test]
+    fn round_trips_line_endings() {
+        for line_ending in LineEnding::ALL {
+            let bytes = encode("一\n二\n", TextEncoding::Utf8, line_ending).unwrap();
+            let document = decode(&bytes, TextEncoding::Utf8);
+            assert_eq!(document.text, "一\n二\n");
+            assert!(document.line_ending == line_ending);
+        }
+    }
+
+    #[test]
+    fn rejects_unmappable_characters() {
+        assert!(encode("🐈", TextEncoding::ShiftJis, LineEnding::Lf).is_err());
+        assert!(encode("🐈", TextEncoding::Utf16Le, LineEnding::Lf).is_ok());
+    }
+}
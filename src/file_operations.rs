@@ -1,22 +1,8 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-pub fn get_txt_files_in_directory(path: PathBuf) -> Vec<PathBuf> {
-    fs::read_dir(path)
-        .unwrap()
-        .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .map(|ext| ext == "txt")
-                .unwrap_or(false)
-        })
-        .map(|entry| entry.path())
-        .collect()
-}
-
 pub fn move_to_trash(path: &PathBuf) -> Result<(), String> {
     let path_str = path
         .to_str()
@@ -64,3 +50,127 @@ pub fn get_txt_files_and_dirs_in_directory(dir: PathBuf) -> Vec<PathBuf> {
     }
     paths
 }
+
+// フォルダ内のファイル数（進捗表示用、リンク先のフォルダはたどらない）
+pub fn count_files(path: &Path) -> u64 {
+    if path.is_dir() && !path.is_symlink() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| count_files(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        1
+    }
+}
+
+// ファイル・フォルダの再帰コピー（フォルダへのリンクはリンクのまま複製する）
+pub fn copy_recursive(
+    source: &Path,
+    destination: &Path,
+    on_file_copied: &mut dyn FnMut(),
+) -> io::Result<()> {
+    if source.is_dir() && source.is_symlink() {
+        copy_symlink(source, destination)?;
+        on_file_copied();
+    } else if source.is_dir() {
+        fs::create_dir_all(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(
+                &entry.path(),
+                &destination.join(entry.file_name()),
+                on_file_copied,
+            )?;
+        }
+    } else {
+        fs::copy(source, destination)?;
+        on_file_copied();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(fs::read_link(source)?, destination)
+}
+
+// ファイル・フォルダの削除（ゴミ箱を使わない）
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+// 重複しない名前の生成（"name (2).txt" の形式）
+pub fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+// 作業用の一時的な名前（".name.label-N" の形式で、まだない名前を選ぶ）
+pub fn temporary_path(dir: &Path, file_name: &str, label: &str) -> PathBuf {
+    (0..)
+        .map(|n| dir.join(format!(".{}.{}-{}", file_name, label, n)))
+        .find(|candidate| !candidate.exists() && !candidate.is_symlink())
+        .unwrap()
+}
+
+// 複数のテキストファイルを一つのファイルに書き出す（フォルダは名前順に展開）
+pub fn concatenate_text_files(paths: &[PathBuf], destination: &Path) -> io::Result<()> {
+    fn collect(path: &Path, files: &mut Vec<PathBuf>) {
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if is_hidden {
+            return;
+        }
+        if path.is_dir() && !path.is_symlink() {
+            let mut children = get_txt_files_and_dirs_in_directory(path.to_path_buf());
+            children.sort();
+            for child in children {
+                collect(&child, files);
+            }
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        collect(path, &mut files);
+    }
+
+    let mut sections = Vec::new();
+    for file in files {
+        sections.push(fs::read_to_string(&file)?);
+    }
+    fs::write(destination, sections.join("\n\n"))
+}
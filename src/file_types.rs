@@ -0,0 +1,121 @@
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EditorMode {
+    PlainText,
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl EditorMode {
+    pub const ALL: [EditorMode; 4] = [
+        EditorMode::PlainText,
+        EditorMode::Markdown,
+        EditorMode::Csv,
+        EditorMode::Json,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EditorMode::PlainText => "Plain Text",
+            EditorMode::Markdown => "Markdown",
+            EditorMode::Csv => "CSV",
+            EditorMode::Json => "JSON",
+        }
+    }
+}
+
+pub struct Template {
+    pub name: &'static str,
+    pub contents: &'static str,
+}
+
+pub struct FileType {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub mode: EditorMode,
+    pub templates: &'static [Template],
+}
+
+impl FileType {
+    // 新規作成時に使う拡張子
+    pub fn default_extension(&self) -> &'static str {
+        self.extensions[0]
+    }
+}
+
+const EMPTY: Template = Template {
+    name: "Empty",
+    contents: "",
+};
+
+// 拡張子とエディタのモードの対応表
+pub const FILE_TYPES: &[FileType] = &[
+    FileType {
+        name: "Text",
+        extensions: &["txt", "text"],
+        mode: EditorMode::PlainText,
+        templates: &[
+            EMPTY,
+            Template {
+                name: "Chapter",
+                contents: "第一章\n\n　\n",
+            },
+            Template {
+                name: "Plot Notes",
+                contents: "タイトル：\nあらすじ：\n\n登場人物：\n\n",
+            },
+        ],
+    },
+    FileType {
+        name: "Markdown",
+        extensions: &["md", "markdown"],
+        mode: EditorMode::Markdown,
+        templates: &[
+            EMPTY,
+            Template {
+                name: "Document",
+                contents: "# Title\n\n",
+            },
+        ],
+    },
+    FileType {
+        name: "CSV",
+        extensions: &["csv", "tsv"],
+        mode: EditorMode::Csv,
+        templates: &[
+            EMPTY,
+            Template {
+                name: "Character List",
+                contents: "name,reading,notes\n",
+            },
+        ],
+    },
+    FileType {
+        name: "JSON",
+        extensions: &["json"],
+        mode: EditorMode::Json,
+        templates: &[Template {
+            name: "Object",
+            contents: "{\n}\n",
+        }],
+    },
+    FileType {
+        name: "Log",
+        extensions: &["log"],
+        mode: EditorMode::PlainText,
+        templates: &[EMPTY],
+    },
+];
+
+pub fn file_type_for(path: &Path) -> Option<&'static FileType> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    FILE_TYPES
+        .iter()
+        .find(|file_type| file_type.extensions.contains(&extension.as_str()))
+}
+
+pub fn editor_mode_for(path: &Path) -> EditorMode {
+    file_type_for(path).map_or(EditorMode::PlainText, |file_type| file_type.mode)
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::Path;
+
+// プロジェクト独自の無視ファイル（書式は .gitignore と同じ）
+pub const PROJECT_IGNORE_FILE: &str = ".txteditorignore";
+
+struct IgnoreRule {
+    pattern: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+// ルートの .gitignore とプロジェクトの無視ファイルのパターン
+#[derive(Default)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    pub fn load(root: &Path) -> Self {
+        let mut rules = Vec::new();
+        for file_name in [".gitignore", PROJECT_IGNORE_FILE] {
+            if let Ok(contents) = fs::read_to_string(root.join(file_name)) {
+                rules.extend(contents.lines().filter_map(parse_rule));
+            }
+        }
+        Self { rules }
+    }
+
+    // relative はルートからの相対パス（区切りは "/"）
+    pub fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let relative: Vec<char> = relative.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+
+        // 後に書かれたパターンが優先される
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let target = if rule.anchored { &relative } else { &name };
+            if glob_match(&rule.pattern, target) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+fn parse_rule(line: &str) -> Option<IgnoreRule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    // 途中に "/" を含むパターンはルートからのパスと照合する
+    let anchored = line.contains('/');
+    let line = line.trim_start_matches('/');
+    if line.is_empty() {
+        return None;
+    }
+
+    Some(IgnoreRule {
+        pattern: line.chars().collect(),
+        negated,
+        dir_only,
+        anchored,
+    })
+}
+
+// "*" "?" "**" "[...]" に対応したグロブ照合
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => match pattern[2..].strip_prefix(&['/']) {
+            // "**/" はフォルダの区切りの直後からのみ照合する
+            Some(rest) => (0..=text.len())
+                .filter(|&i| i == 0 || text[i - 1] == '/')
+                .any(|i| glob_match(rest, &text[i..])),
+            None => (0..=text.len()).any(|i| glob_match(&pattern[2..], &text[i..])),
+        },
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            text.first().is_some_and(|&c| c != '/') && glob_match(&pattern[1..], &text[1..])
+        }
+        Some('[') => match pattern.iter().position(|&c| c == ']') {
+            Some(end) if end > 1 => {
+                let Some(&c) = text.first() else {
+                    return false;
+                };
+                let class = &pattern[1..end];
+                let (negated, class) = match class.first() {
+                    Some('!') | Some('^') => (true, &class[1..]),
+                    _ => (false, class),
+                };
+                let mut matched = false;
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        matched |= (class[i]..=class[i + 2]).contains(&c);
+                        i += 3;
+                    } else {
+                        matched |= class[i] == c;
+                        i += 1;
+                    }
+                }
+                matched != negated && glob_match(&pattern[end + 1..], &text[1..])
+            }
+            _ => text.first() == Some(&'[') && glob_match(&pattern[1..], &text[1..]),
+        },
+        Some(&p) => text.first() == Some(&p) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> IgnoreRules {
+        IgnoreRules {
+            rules: source.lines().filter_map(parse_rule).collect(),
+        }
+    }
+
+    #[test]
+    fn matches_names_anywhere() {
+        let rules = parse("# コメント\n*.log\n!keep.log\nbuild/\n");
+        assert!(rules.is_ignored("a.log", false));
+        assert!(rules.is_ignored("dir/a.log", false));
+        assert!(!rules.is_ignored("dir/keep.log", false));
+        assert!(rules.is_ignored("sub/build", true));
+        assert!(!rules.is_ignored("sub/build", false));
+    }
+
+    #[test]
+    fn anchors_patterns_with_slashes() {
+        let rules = parse("/target\ndocs/a?c\n");
+        assert!(rules.is_ignored("target", true));
+        assert!(!rules.is_ignored("sub/target", true));
+        assert!(rules.is_ignored("docs/abc", false));
+        assert!(!rules.is_ignored("docs/a/c", false));
+        assert!(!rules.is_ignored("other/docs/abc", false));
+    }
+
+    #[test]
+    fn matches_double_star() {
+        let rules = parse("**/drafts\nnotes/**/*.bak\n");
+        assert!(rules.is_ignored("drafts", true));
+        assert!(rules.is_ignored("a/b/drafts", true));
+        assert!(!rules.is_ignored("a/mydrafts", true));
+        assert!(rules.is_ignored("notes/x.bak", false));
+        assert!(rules.is_ignored("notes/a/b/x.bak", false));
+        assert!(!rules.is_ignored("other/x.bak", false));
+        // "*" はフォルダの区切りをまたがない
+        assert!(!parse("/*.txt").is_ignored("sub/a.txt", false));
+    }
+
+    #[test]
+    fn matches_character_classes() {
+        let rules = parse("[0-9]*.txt\n[!a-z]*.md\n");
+        assert!(rules.is_ignored("1a.txt", false));
+        assert!(!rules.is_ignored("a1.txt", false));
+        assert!(rules.is_ignored("_readme.md", false));
+        assert!(!rules.is_ignored("readme.md", false));
+    }
+}
@@ -1,6 +1,15 @@
 mod app;
+mod batch_rename;
+mod clipboard;
+mod document;
 mod file_operations;
+mod file_types;
+mod ignore;
+mod tags;
+mod text;
+mod tree;
 mod ui;
+mod workspace;
 
 use eframe::NativeOptions;
 
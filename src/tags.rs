@@ -0,0 +1,113 @@
+use crate::workspace::{read_metadata, relative_key, write_metadata};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const TAGS_FILE: &str = "tags";
+
+// ファイル・フォルダに付けたタグ（1行に「相対パス<TAB>タグ,タグ」）
+#[derive(Default)]
+pub struct TagStore {
+    root: PathBuf,
+    tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TagStore {
+    pub fn load(root: &Path) -> Self {
+        let mut tags = BTreeMap::new();
+        if let Some(contents) = read_metadata(root, TAGS_FILE) {
+            for line in contents.lines() {
+                if let Some((path, names)) = line.split_once('\t') {
+                    let names: BTreeSet<String> = names
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    if !names.is_empty() {
+                        tags.insert(path.to_string(), names);
+                    }
+                }
+            }
+        }
+        Self {
+            root: root.to_path_buf(),
+            tags,
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if self.root.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let contents: String = self
+            .tags
+            .iter()
+            .map(|(path, names)| {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                format!("{}\t{}\n", path, names.join(","))
+            })
+            .collect();
+        write_metadata(&self.root, TAGS_FILE, &contents)
+    }
+
+    pub fn tags_for(&self, path: &Path) -> Option<&BTreeSet<String>> {
+        self.tags.get(&relative_key(&self.root, path)?)
+    }
+
+    pub fn add(&mut self, path: &Path, tag: &str) {
+        if !is_valid_tag(tag) {
+            return;
+        }
+        if let Some(key) = relative_key(&self.root, path) {
+            self.tags.entry(key).or_default().insert(tag.to_string());
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path, tag: &str) {
+        if let Some(key) = relative_key(&self.root, path) {
+            if let Some(names) = self.tags.get_mut(&key) {
+                names.remove(tag);
+                if names.is_empty() {
+                    self.tags.remove(&key);
+                }
+            }
+        }
+    }
+
+    // 移動・名前変更に合わせてフォルダ配下のタグも付け替える
+    pub fn path_moved(&mut self, from: &Path, to: &Path) {
+        let (Some(from_key), Some(to_key)) =
+            (relative_key(&self.root, from), relative_key(&self.root, to))
+        else {
+            return;
+        };
+        let moved: Vec<String> = self
+            .tags
+            .keys()
+            .filter(|key| is_same_or_child(key, &from_key))
+            .cloned()
+            .collect();
+        for key in moved {
+            let names = self.tags.remove(&key).unwrap();
+            let new_key = format!("{}{}", to_key, &key[from_key.len()..]);
+            self.tags.insert(new_key, names);
+        }
+    }
+
+    pub fn path_removed(&mut self, path: &Path) {
+        if let Some(removed_key) = relative_key(&self.root, path) {
+            self.tags
+                .retain(|key, _| !is_same_or_child(key, &removed_key));
+        }
+    }
+}
+
+// タグ名にはファイルの区切り（タブ・カンマ・改行）を使えない
+pub fn is_valid_tag(tag: &str) -> bool {
+    !tag.trim().is_empty() && !tag.contains([',', '\t', '\n', '\r'])
+}
+
+fn is_same_or_child(key: &str, parent: &str) -> bool {
+    key == parent || key.starts_with(&format!("{}/", parent))
+}
@@ -0,0 +1,256 @@
+use crate::text::emphasis::{find_emphasis, strip_markup};
+use regex::Regex;
+
+// エディタの記法
+// 見出しは "# " "## " "### "（大・中・小見出し）、改ページは "---" だけの行、
+// 傍点は "《《強調》》"、ルビは青空文庫と同じ "｜親文字《ルビ》"
+pub const PAGE_BREAK: &str = "---";
+const HEADINGS: [(&str, &str); 3] = [("# ", "大"), ("## ", "中"), ("### ", "小")];
+
+// 本文の前に置く記号の説明
+const NOTATION_RULE: &str = "-------------------------------------------------------";
+const NOTATION: &str = "【テキスト中に現れる記号について】\n\n\
+    《》：ルビ\n\
+    （例）吾輩《わがはい》\n\n\
+    ｜：ルビの付く文字列の始まりを特定する記号\n\
+    （例）一番｜年嵩《としかさ》\n\n\
+    ［＃］：入力者注　主に外字の説明や、傍点の位置の指定";
+
+pub struct ExportOptions {
+    pub title: String,
+    pub author: String,
+}
+
+// 青空文庫形式のテキストを作る（改行は呼び出し側で CRLF にする）
+pub fn export(text: &str, options: &ExportOptions) -> String {
+    let mut lines = vec![
+        options.title.clone(),
+        options.author.clone(),
+        String::new(),
+        NOTATION_RULE.to_string(),
+        NOTATION.to_string(),
+        NOTATION_RULE.to_string(),
+    ];
+    let body = text.trim_end_matches('\n');
+    lines.extend(body.split('\n').map(export_line));
+
+    // 後付け（底本の情報）は本文になければ追加する
+    if !body.lines().any(|line| line.starts_with("底本：")) {
+        lines.extend([
+            String::new(),
+            String::new(),
+            String::new(),
+            "底本：未刊行原稿".to_string(),
+            format!("入力：{}", options.author),
+        ]);
+    }
+    let mut aozora = lines.join("\n");
+    aozora.push('\n');
+    aozora
+}
+
+fn export_line(line: &str) -> String {
+    if line == PAGE_BREAK {
+        return "［＃改ページ］".to_string();
+    }
+    for (prefix, size) in HEADINGS.iter().rev() {
+        if let Some(heading) = line.strip_prefix(prefix) {
+            // 注記の対象は傍点の記法を除いた文字列
+            let target = strip_markup(heading.trim());
+            return format!(
+                "［＃３字下げ］{}［＃「{}」は{}見出し］",
+                export_emphasis(heading.trim()),
+                target,
+                size
+            );
+        }
+    }
+    export_emphasis(line)
+}
+
+// "《《強調》》" を "強調［＃「強調」に傍点］" にする
+fn export_emphasis(line: &str) -> String {
+    let mut exported = String::with_capacity(line.len());
+    let mut last = 0;
+    for emphasis in find_emphasis(line) {
+        let text = &line[emphasis.text];
+        exported.push_str(&line[last..emphasis.range.start]);
+        exported.push_str(&format!("{}［＃「{}」に傍点］", text, text));
+        last = emphasis.range.end;
+    }
+    exported.push_str(&line[last..]);
+    exported
+}
+
+// 青空文庫形式のテキストをエディタの記法に変換する
+// 表題と著者名は見出しと本文の先頭に残し、記号の説明は取り除く
+pub fn import(aozora: &str) -> String {
+    let lines: Vec<&str> = aozora.lines().collect();
+    let mut output = Vec::new();
+
+    // 冒頭の表題・著者名（記号の説明の前まで）
+    let notation = lines
+        .iter()
+        .position(|line| line.starts_with("-----"))
+        .filter(|&index| index < 20);
+    let body_start = match notation {
+        Some(start) => {
+            for (index, line) in lines[..start].iter().enumerate() {
+                match (index, line.trim()) {
+                    (_, "") => {}
+                    (0, title) => output.push(format!("# {}", title)),
+                    (_, line) => output.push(line.to_string()),
+                }
+            }
+            lines[start + 1..]
+                .iter()
+                .position(|line| line.starts_with("-----"))
+                .map_or(start + 1, |end| start + end + 2)
+        }
+        None => 0,
+    };
+
+    // 後付けは「底本：」の行から
+    let body_end = lines
+        .iter()
+        .rposition(|line| line.starts_with("底本："))
+        .filter(|&index| index >= body_start)
+        .unwrap_or(lines.len());
+
+    let annotations = Annotations::new();
+    for line in &lines[body_start..body_end] {
+        output.push(annotations.import_line(line));
+    }
+    while output.last().is_some_and(|line| line.is_empty()) {
+        output.pop();
+    }
+    if body_end < lines.len() {
+        output.push(String::new());
+        output.extend(lines[body_end..].iter().map(|line| line.to_string()));
+    }
+
+    let mut text = output.join("\n");
+    text.push('\n');
+    text
+}
+
+struct Annotations {
+    page_break: Regex,
+    heading: Regex,
+    heading_block: Regex,
+    emphasis: Regex,
+    emphasis_block: Regex,
+    indent: Regex,
+}
+
+impl Annotations {
+    fn new() -> Self {
+        Self {
+            page_break: Regex::new(r"^［＃(改ページ|改丁|改見開き|改段)］$").unwrap(),
+            heading: Regex::new(r"［＃「([^」]+)」は(同行)?(大|中|小)見出し］").unwrap(),
+            heading_block: Regex::new(r"［＃(大|中|小)見出し］(.*?)［＃(大|中|小)見出し終わり］")
+                .unwrap(),
+            emphasis: Regex::new(r"［＃「([^」]+)」に[^］]*傍点］").unwrap(),
+            emphasis_block: Regex::new(r"［＃[^］]*傍点］(.*?)［＃[^］]*傍点終わり］").unwrap(),
+            indent: Regex::new(
+                r"［＃(地から)?[０-９0-9]+字(下げ|上げ)(終わり)?］|［＃ここで字下げ終わり］",
+            )
+            .unwrap(),
+        }
+    }
+
+    fn import_line(&self, line: &str) -> String {
+        if self.page_break.is_match(line.trim()) {
+            return PAGE_BREAK.to_string();
+        }
+
+        let line = self.indent.replace_all(line, "");
+        let line = self
+            .emphasis_block
+            .replace_all(&line, "《《$1》》")
+            .into_owned();
+        let line = replace_target(&self.emphasis, &line, |text| format!("《《{}》》", text));
+
+        // 見出しは行全体を見出しにする
+        let level = |size: &str| match size {
+            "大" => "# ",
+            "中" => "## ",
+            _ => "### ",
+        };
+        if let Some(captures) = self.heading_block.captures(&line) {
+            let heading = self.heading_block.replace_all(&line, "$2");
+            return format!("{}{}", level(&captures[1]), heading.trim());
+        }
+        if let Some(captures) = self.heading.captures(&line) {
+            let prefix = level(&captures[3]);
+            let heading = self.heading.replace_all(&line, "");
+            return format!("{}{}", prefix, heading.trim());
+        }
+        line
+    }
+}
+
+// "対象［＃「対象」に…］" の形の注記を、直前の対象の文字列ごと置き換える
+fn replace_target(annotation: &Regex, line: &str, replace: impl Fn(&str) -> String) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut last = 0;
+    for captures in annotation.captures_iter(line) {
+        let whole = captures.get(0).unwrap();
+        let target = &captures[1];
+        let before = &line[last..whole.start()];
+        match before.strip_suffix(target) {
+            Some(prefix) => {
+                result.push_str(prefix);
+                result.push_str(&replace(target));
+            }
+            // 対象が直前にない注記はそのまま残す
+            None => {
+                result.push_str(before);
+                result.push_str(whole.as_str());
+            }
+        }
+        last = whole.end();
+    }
+    result.push_str(&line[last..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            title: "題名".to_string(),
+            author: "著者".to_string(),
+        }
+    }
+
+    #[test]
+    fn exports_annotations() {
+        let aozora = export("# 第一章\n　《《強調》》です\n---\n", &options());
+        assert!(aozora.starts_with("題名\n著者\n\n"));
+        assert!(aozora.contains("\n［＃３字下げ］第一章［＃「第一章」は大見出し］\n"));
+        assert!(aozora.contains("\n　強調［＃「強調」に傍点］です\n"));
+        assert!(aozora.contains("\n［＃改ページ］\n"));
+        assert!(aozora.ends_with("底本：未刊行原稿\n入力：著者\n"));
+    }
+
+    #[test]
+    fn round_trips_through_export() {
+        let body = "# 第一章\n\n　《《強調》》と｜漢字《かんじ》。\n## 《《節》》\n---\n　続き\n";
+        let imported = import(&export(body, &options()));
+        assert_eq!(
+            imported,
+            format!("# 題名\n著者\n{}\n底本：未刊行原稿\n入力：著者\n", body)
+        );
+    }
+
+    #[test]
+    fn imports_block_annotations() {
+        let text = "［＃中見出し］見出し［＃中見出し終わり］\n［＃傍点］強調［＃傍点終わり］した\n［＃改丁］";
+        assert_eq!(import(text), "## 見出し\n《《強調》》した\n---\n");
+        // 対象が直前にない注記は残す
+        assert_eq!(import("別［＃「強調」に傍点］"), "別［＃「強調」に傍点］\n");
+    }
+}
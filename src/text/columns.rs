@@ -0,0 +1,48 @@
+// 東アジアの文字幅（半角 1、全角 2）
+// 曖昧な幅の記号（…、―、○、矢印など）は日本語の文書に合わせて全角として扱う
+pub fn char_width(c: char) -> usize {
+    let wide = matches!(c,
+        '\u{1100}'..='\u{115F}'
+        | '\u{2010}'..='\u{2027}'
+        | '\u{2030}'..='\u{2033}'
+        | '\u{203B}'
+        | '\u{2190}'..='\u{21FF}'
+        | '\u{2460}'..='\u{24FF}'
+        | '\u{2500}'..='\u{257F}'
+        | '\u{25A0}'..='\u{27BF}'
+        | '\u{2E80}'..='\u{303E}'
+        | '\u{3040}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1F64F}'
+        | '\u{20000}'..='\u{3FFFD}'
+    );
+    if wide {
+        2
+    } else {
+        1
+    }
+}
+
+// "20, 40" のような桁の指定を読む（数字でないものと 0 は無視する）
+pub fn parse_rulers(input: &str) -> Vec<usize> {
+    let mut columns: Vec<usize> = input
+        .split([',', '、', ' ', '　'])
+        .filter_map(|column| column.trim().parse().ok())
+        .filter(|&column| column > 0)
+        .collect();
+    columns.sort_unstable();
+    columns.dedup();
+    columns
+}
+
+pub fn format_rulers(columns: &[usize]) -> String {
+    columns
+        .iter()
+        .map(|column| column.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,388 @@
+// 全角英数字を半角に
+pub fn to_half_width_alnum(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => {
+                char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+            }
+            _ => c,
+        })
+        .collect()
+}
+
+// 半角英数字を全角に
+pub fn to_full_width_alnum(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '0'..='9' | 'A'..='Z' | 'a'..='z' => char::from_u32(c as u32 + 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+// 半角カタカナを全角に（濁点・半濁点は前の文字と合わせて一文字にする）
+pub fn to_full_width_katakana(text: &str) -> String {
+    const KANA: &str = "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+    let kana: Vec<char> = KANA.chars().collect();
+    let mut converted = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let full = match c {
+            '｡' => '。',
+            '｢' => '「',
+            '｣' => '」',
+            '､' => '、',
+            '･' => '・',
+            'ﾞ' => '゛',
+            'ﾟ' => '゜',
+            'ｦ'..='ﾝ' => kana[(c as u32 - 'ｦ' as u32) as usize],
+            _ => {
+                converted.push(c);
+                continue;
+            }
+        };
+        let combined = match chars.peek() {
+            Some('ﾞ') if full == 'ウ' => Some('ヴ'),
+            Some('ﾞ') if matches!(full, 'カ'..='ト' | 'ハ'..='ホ') => voiced(full, 1),
+            Some('ﾟ') if matches!(full, 'ハ'..='ホ') => voiced(full, 2),
+            _ => None,
+        };
+        match combined {
+            Some(combined) => {
+                chars.next();
+                converted.push(combined);
+            }
+            None => converted.push(full),
+        }
+    }
+    converted
+}
+
+// カ行〜タ行・ハ行の濁音（offset 1）と半濁音（offset 2）
+fn voiced(c: char, offset: u32) -> Option<char> {
+    // 清音と濁音が交互に並ぶ範囲だけ（ッ は例外でツの前にある）
+    let index = c as u32;
+    let base = match c {
+        'カ'..='チ' => (index - 'カ' as u32).is_multiple_of(2),
+        'ツ'..='ト' => (index - 'ツ' as u32).is_multiple_of(2),
+        'ハ'..='ホ' => (index - 'ハ' as u32).is_multiple_of(3),
+        _ => false,
+    };
+    base.then(|| char::from_u32(index + offset)).flatten()
+}
+
+// ひらがなをカタカナに
+pub fn to_katakana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ぁ'..='ゖ' | 'ゝ' | 'ゞ' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+// カタカナをひらがなに（対応するひらがながない文字はそのまま）
+pub fn to_hiragana(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' | 'ヽ' | 'ヾ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+const KANJI_DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+// 十・百・千の位と、四桁ごとの万・億・兆
+const SMALL_UNITS: [(char, u64); 3] = [('千', 1000), ('百', 100), ('十', 10)];
+const LARGE_UNITS: [(char, u64); 3] = [
+    ('兆', 1_000_000_000_000),
+    ('億', 100_000_000),
+    ('万', 10_000),
+];
+
+// 算用数字を漢数字に（"1234" は "千二百三十四"、0 で始まる数は一桁ずつ "〇〇七"）
+pub fn to_kanji_numerals(text: &str) -> String {
+    let mut converted = String::with_capacity(text.len());
+    let mut digits = String::new();
+    for c in text.chars().chain(['\0']) {
+        // 全角の数字も変換する
+        let digit = match c {
+            '0'..='9' => Some(c),
+            '０'..='９' => char::from_u32(c as u32 - 0xFEE0),
+            _ => None,
+        };
+        if let Some(digit) = digit {
+            digits.push(digit);
+            continue;
+        }
+        if !digits.is_empty() {
+            converted.push_str(&kanji_number(&digits));
+            digits.clear();
+        }
+        if c != '\0' {
+            converted.push(c);
+        }
+    }
+    converted
+}
+
+fn kanji_number(digits: &str) -> String {
+    let value = digits
+        .parse::<u64>()
+        .ok()
+        .filter(|&value| value < 10_000_000_000_000_000);
+    let Some(value) = value.filter(|_| digits.len() == 1 || !digits.starts_with('0')) else {
+        return digits
+            .chars()
+            .map(|c| KANJI_DIGITS[c.to_digit(10).unwrap() as usize])
+            .collect();
+    };
+    if value == 0 {
+        return "〇".to_string();
+    }
+
+    let mut kanji = String::new();
+    let mut rest = value;
+    for (unit, size) in LARGE_UNITS {
+        let section = rest / size;
+        if section > 0 {
+            kanji.push_str(&kanji_section(section));
+            kanji.push(unit);
+        }
+        rest %= size;
+    }
+    kanji.push_str(&kanji_section(rest));
+    kanji
+}
+
+// 一万未満の数（位の前の "一" は省く）
+fn kanji_section(value: u64) -> String {
+    let mut kanji = String::new();
+    let mut rest = value;
+    for (unit, size) in SMALL_UNITS {
+        let digit = rest / size;
+        if digit > 1 {
+            kanji.push(KANJI_DIGITS[digit as usize]);
+        }
+        if digit > 0 {
+            kanji.push(unit);
+        }
+        rest %= size;
+    }
+    if rest > 0 {
+        kanji.push(KANJI_DIGITS[rest as usize]);
+    }
+    kanji
+}
+
+// 漢数字を算用数字に（"二千二十五" も "二〇二五" も "2025"）
+// 数字を含まない "万" などだけの並びや、数字以外の漢字に続く並び（"統一" など）、
+// 一文字だけで漢字が続く並び（"一緒" "十分" "一人前" など）は変換しない
+pub fn to_arabic_numerals(text: &str) -> String {
+    let mut converted = String::with_capacity(text.len());
+    let mut run = String::new();
+    let mut previous = '\0';
+    let mut run_previous = '\0';
+    for c in text.chars().chain(['\0']) {
+        if is_kanji_numeral(c) {
+            if run.is_empty() {
+                run_previous = previous;
+            }
+            run.push(c);
+        } else {
+            if !run.is_empty() {
+                let in_word = follows_word(run_previous) || precedes_word(&run, run_previous, c);
+                let number = (!in_word).then(|| parse_kanji_number(&run)).flatten();
+                converted.push_str(number.as_deref().unwrap_or(&run));
+                run.clear();
+            }
+            if c != '\0' {
+                converted.push(c);
+            }
+        }
+        previous = c;
+    }
+    converted
+}
+
+pub fn is_kanji_numeral(c: char) -> bool {
+    KANJI_DIGITS.contains(&c)
+        || SMALL_UNITS.iter().any(|&(unit, _)| unit == c)
+        || LARGE_UNITS.iter().any(|&(unit, _)| unit == c)
+}
+
+// 直前が数字以外の漢字なら熟語の一部とみなす（"第一" などの接頭辞は除く）
+fn follows_word(previous: char) -> bool {
+    crate::text::stats::is_kanji(previous) && !is_number_prefix(previous)
+}
+
+// 一文字の並びに漢字が続くときも熟語の一部とみなす
+// "二〇二五年" "三十人" のように二文字以上か、"第一章" のように接頭辞に続く場合だけ数とする
+fn precedes_word(run: &str, previous: char, next: char) -> bool {
+    crate::text::stats::is_kanji(next) && run.chars().count() == 1 && !is_number_prefix(previous)
+}
+
+fn is_number_prefix(c: char) -> bool {
+    matches!(c, '第' | '約' | '計' | '全' | '各' | '毎')
+}
+
+fn parse_kanji_number(run: &str) -> Option<String> {
+    let digit = |c: char| KANJI_DIGITS.iter().position(|&d| d == c).map(|d| d as u64);
+    let has_unit = run.chars().any(|c| digit(c).is_none());
+    if !has_unit {
+        // 一桁ずつの表記はそのまま並べる（先頭の 〇 も残す）
+        return Some(
+            run.chars()
+                .filter_map(digit)
+                .map(|d| d.to_string())
+                .collect(),
+        );
+    }
+    if !run
+        .chars()
+        .any(|c| digit(c).is_some() || matches!(c, '十' | '百' | '千'))
+    {
+        return None;
+    }
+
+    let mut total = 0u64;
+    let mut section = 0u64;
+    let mut current: Option<u64> = None;
+    for c in run.chars() {
+        if let Some(d) = digit(c) {
+            // 位を挟まずに数字が続く表記（"二五百" など）は変換しない
+            if current.is_some() {
+                return None;
+            }
+            current = Some(d);
+        } else if let Some(&(_, size)) = SMALL_UNITS.iter().find(|&&(unit, _)| unit == c) {
+            section += current.take().unwrap_or(1) * size;
+        } else if let Some(&(_, size)) = LARGE_UNITS.iter().find(|&&(unit, _)| unit == c) {
+            section += current.take().unwrap_or(0);
+            if section == 0 {
+                return None;
+            }
+            total = total.checked_add(section.checked_mul(size)?)?;
+            section = 0;
+        }
+    }
+    total = total.checked_add(section + current.unwrap_or(0))?;
+    Some(total.to_string())
+}
+
+// エディタから使う変換
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextConversion {
+    HalfWidthAlnum,
+    FullWidthAlnum,
+    FullWidthKatakana,
+    Katakana,
+    Hiragana,
+    KanjiNumerals,
+    ArabicNumerals,
+}
+
+impl TextConversion {
+    pub const ALL: [TextConversion; 7] = [
+        TextConversion::HalfWidthAlnum,
+        TextConversion::FullWidthAlnum,
+        TextConversion::FullWidthKatakana,
+        TextConversion::Katakana,
+        TextConversion::Hiragana,
+        TextConversion::KanjiNumerals,
+        TextConversion::ArabicNumerals,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextConversion::HalfWidthAlnum => "Full-width → Half-width Alphanumerics",
+            TextConversion::FullWidthAlnum => "Half-width → Full-width Alphanumerics",
+            TextConversion::FullWidthKatakana => "Half-width → Full-width Katakana",
+            TextConversion::Katakana => "Hiragana → Katakana",
+            TextConversion::Hiragana => "Katakana → Hiragana",
+            TextConversion::KanjiNumerals => "Arabic → Kanji Numerals",
+            TextConversion::ArabicNumerals => "Kanji → Arabic Numerals",
+        }
+    }
+
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            TextConversion::HalfWidthAlnum => to_half_width_alnum(text),
+            TextConversion::FullWidthAlnum => to_full_width_alnum(text),
+            TextConversion::FullWidthKatakana => to_full_width_katakana(text),
+            TextConversion::Katakana => to_katakana(text),
+            TextConversion::Hiragana => to_hiragana(text),
+            TextConversion::KanjiNumerals => to_kanji_numerals(text),
+            TextConversion::ArabicNumerals => to_arabic_numerals(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_alnum_width() {
+        assert_eq!(to_half_width_alnum("ＡＢｃ１２３。"), "ABc123。");
+        assert_eq!(to_full_width_alnum("ABc123-"), "ＡＢｃ１２３-");
+    }
+
+    #[test]
+    fn merges_dakuten_into_full_width_katakana() {
+        assert_eq!(to_full_width_katakana("ﾊﾟｿｺﾝ"), "パソコン");
+        assert_eq!(to_full_width_katakana("ｶﾞｯｺｳ"), "ガッコウ");
+        assert_eq!(to_full_width_katakana("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+        assert_eq!(to_full_width_katakana("ﾄﾞｱ"), "ドア");
+        // 濁点が付かない文字は濁点を別に残す
+        assert_eq!(to_full_width_katakana("ｱﾞ"), "ア゛");
+        assert_eq!(to_full_width_katakana("ｶﾟ"), "カ゜");
+        assert_eq!(to_full_width_katakana("｢ﾃｽﾄ｣｡"), "「テスト」。");
+    }
+
+    #[test]
+    fn converts_kana() {
+        assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
+        assert_eq!(to_hiragana("カタカナ・ヴ"), "かたかな・ゔ");
+    }
+
+    #[test]
+    fn writes_kanji_numerals() {
+        assert_eq!(to_kanji_numerals("1234"), "千二百三十四");
+        assert_eq!(to_kanji_numerals("2025年"), "二千二十五年");
+        assert_eq!(to_kanji_numerals("10000"), "一万");
+        assert_eq!(to_kanji_numerals("110000"), "十一万");
+        assert_eq!(to_kanji_numerals("0"), "〇");
+        assert_eq!(to_kanji_numerals("007"), "〇〇七");
+        assert_eq!(to_kanji_numerals("第３章"), "第三章");
+    }
+
+    #[test]
+    fn reads_kanji_numerals() {
+        assert_eq!(to_arabic_numerals("二〇二五年"), "2025年");
+        assert_eq!(to_arabic_numerals("二千二十五年"), "2025年");
+        assert_eq!(to_arabic_numerals("三十人"), "30人");
+        assert_eq!(to_arabic_numerals("十一万"), "110000");
+        assert_eq!(to_arabic_numerals("一億二千万"), "120000000");
+        assert_eq!(to_arabic_numerals("第一章"), "第1章");
+        assert_eq!(to_arabic_numerals("約十"), "約10");
+    }
+
+    #[test]
+    fn leaves_words_with_numerals() {
+        for word in ["一緒", "一般", "十分", "一人前", "統一", "万一", "唯一無二"]
+        {
+            assert_eq!(to_arabic_numerals(word), word);
+        }
+        // 位を挟まない並びや数字のない位だけの並び
+        assert_eq!(to_arabic_numerals("二五百"), "二五百");
+        assert_eq!(to_arabic_numerals("万億"), "万億");
+    }
+
+    #[test]
+    fn round_trips_numbers() {
+        for number in ["1", "10", "15", "100", "1001", "20250", "123456789"] {
+            assert_eq!(to_arabic_numerals(&to_kanji_numerals(number)), number);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::text::emphasis::{self, find_emphasis};
+use crate::text::ruby::{self, find_rubies};
+use regex::{Captures, Regex};
+
+// 投稿サイトごとの記法
+// エディタの記法はカクヨムと同じ（ルビ "｜親文字《ルビ》"、傍点 "《《強調》》"）
+// 小説家になろうには傍点の記法がなく、一文字ずつ "・" のルビを振る
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dialect {
+    Narou,
+    Kakuyomu,
+    Plain,
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 3] = [Dialect::Narou, Dialect::Kakuyomu, Dialect::Plain];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Dialect::Narou => "Shōsetsuka ni Narō",
+            Dialect::Kakuyomu => "Kakuyomu",
+            Dialect::Plain => "Plain text",
+        }
+    }
+}
+
+// 段落の間の空行の扱い
+#[derive(Clone, Copy, PartialEq)]
+pub enum LineSpacing {
+    Keep,
+    // 段落ごとに空行を入れる（Web 小説でよく使われる書き方）
+    Add,
+    // 段落の間の空行を一行ずつ減らす（Add の逆）
+    Remove,
+}
+
+impl LineSpacing {
+    pub const ALL: [LineSpacing; 3] = [LineSpacing::Keep, LineSpacing::Add, LineSpacing::Remove];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineSpacing::Keep => "Keep blank lines",
+            LineSpacing::Add => "Blank line between paragraphs",
+            LineSpacing::Remove => "Remove one blank line between paragraphs",
+        }
+    }
+}
+
+// 変換できなかった記法（行番号は変換後の 1 始まり）
+pub struct Issue {
+    pub line: usize,
+    pub message: String,
+}
+
+pub struct Conversion {
+    pub text: String,
+    pub issues: Vec<Issue>,
+}
+
+// なろうのルビの上限（超えるとルビとして表示されない）
+const NAROU_MAX_BASE: usize = 20;
+const NAROU_MAX_RUBY: usize = 10;
+
+pub fn convert(text: &str, from: Dialect, to: Dialect, spacing: LineSpacing) -> Conversion {
+    let patterns = Patterns::new();
+    let mut issues = Vec::new();
+    let mut lines = Vec::new();
+
+    for (index, line) in text.split('\n').enumerate() {
+        let mut report = |message: String| {
+            issues.push(Issue {
+                line: index + 1,
+                message,
+            })
+        };
+        let line = match from {
+            Dialect::Narou => patterns.parse_narou(line),
+            Dialect::Kakuyomu | Dialect::Plain => line.to_string(),
+        };
+        let line = match to {
+            Dialect::Narou => patterns.write_narou(&line, &mut report),
+            Dialect::Kakuyomu => line,
+            Dialect::Plain => ruby::strip_markup(&emphasis::strip_markup(&line)),
+        };
+        if to != Dialect::Plain {
+            check_common(&line, &mut report);
+        }
+        lines.push(line);
+    }
+
+    // 行ごとに変換して改行で戻すので、末尾の改行の有無は元の文書のまま
+    let (text, positions) = match spacing {
+        LineSpacing::Keep => (lines.join("\n"), (0..lines.len()).collect()),
+        LineSpacing::Add => add_blank_lines(&lines),
+        LineSpacing::Remove => remove_blank_lines(&lines),
+    };
+    // 空行を増減したときは、変換後の行番号に合わせる
+    for issue in &mut issues {
+        issue.line = positions[issue.line - 1] + 1;
+    }
+    Conversion { text, issues }
+}
+
+struct Patterns {
+    // "｜親文字(るび)" と "漢字(るび)"（読みがかなだけのときルビになる）
+    paren_ruby: Regex,
+    // "｜強《・》｜調《・》" のような一文字ずつの傍点
+    dot_ruby: Regex,
+    dot_ruby_char: Regex,
+    // なろうでルビと解釈されてしまう括弧
+    implicit_paren: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        Self {
+            paren_ruby: Regex::new(
+                r"(?:[|｜]([^|｜《》()（）]+)|([\p{Han}々〆ヶ]+))[(（]([ぁ-ゖァ-ヺー・]+)[)）]",
+            )
+            .unwrap(),
+            dot_ruby: Regex::new(r"(?:[|｜][^|｜《》]《[・﹅]》)+").unwrap(),
+            dot_ruby_char: Regex::new(r"[|｜]([^|｜《》])《[・﹅]》").unwrap(),
+            implicit_paren: Regex::new(
+                r"(?:[|｜][^|｜《》()（）]+|[\p{Han}々〆ヶ]+)[(（][ぁ-ゖァ-ヺー・]+[)）]",
+            )
+            .unwrap(),
+        }
+    }
+
+    fn parse_narou(&self, line: &str) -> String {
+        let line = self
+            .dot_ruby
+            .replace_all(line, |captures: &Captures| {
+                let text: String = self
+                    .dot_ruby_char
+                    .captures_iter(&captures[0])
+                    .map(|c| c[1].to_string())
+                    .collect();
+                emphasis::wrap(&text)
+            })
+            .into_owned();
+        self.paren_ruby
+            .replace_all(&line, |captures: &Captures| {
+                let base = captures.get(1).or(captures.get(2)).unwrap().as_str();
+                ruby::wrap(base, &captures[3])
+            })
+            .into_owned()
+    }
+
+    fn write_narou(&self, line: &str, report: &mut impl FnMut(String)) -> String {
+        // 記法に変換する前の括弧はなろうでルビになってしまう
+        for found in self.implicit_paren.find_iter(line) {
+            report(format!(
+                "\"{}\" will be shown as ruby on Shōsetsuka ni Narō",
+                found.as_str()
+            ));
+        }
+
+        let mut converted = String::with_capacity(line.len());
+        let mut last = 0;
+        for mark in find_emphasis(line) {
+            converted.push_str(&line[last..mark.range.start]);
+            let text = &line[mark.text.clone()];
+            if text.contains(['《', '》', '|', '｜']) {
+                // ルビと傍点は同じ文字に重ねられない
+                report(format!(
+                    "Emphasis over ruby \"{}\" was removed",
+                    &line[mark.range.clone()]
+                ));
+                converted.push_str(text);
+            } else {
+                for c in text.chars() {
+                    converted.push_str(&ruby::wrap(&c.to_string(), "・"));
+                }
+            }
+            last = mark.range.end;
+        }
+        converted.push_str(&line[last..]);
+
+        for found in find_rubies(&converted) {
+            let base = converted[found.base.clone()].chars().count();
+            let reading = converted[found.ruby.clone()].chars().count();
+            if base > NAROU_MAX_BASE || reading > NAROU_MAX_RUBY {
+                report(format!(
+                    "Ruby \"{}\" is too long for Shōsetsuka ni Narō (base {} / ruby {} characters max)",
+                    &converted[found.range.clone()],
+                    NAROU_MAX_BASE,
+                    NAROU_MAX_RUBY
+                ));
+            }
+        }
+        converted
+    }
+}
+
+// どちらのサイトでも表示できない記法
+fn check_common(line: &str, report: &mut impl FnMut(String)) {
+    if line.contains("［＃") {
+        report("Aozora Bunko annotations are not supported".to_string());
+    }
+    let stripped = ruby::strip_markup(&emphasis::strip_markup(line));
+    if stripped.contains('《') && stripped.contains('》') {
+        report("Ruby without a base text is shown as is".to_string());
+    } else if stripped.contains('《') {
+        report("Unclosed 《".to_string());
+    }
+}
+
+// 変換後の文書と、各行の変換後の位置（0 始まり）を返す
+fn add_blank_lines(lines: &[String]) -> (String, Vec<usize>) {
+    let mut output: Vec<&str> = Vec::with_capacity(lines.len() * 2);
+    let mut positions = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        positions.push(output.len());
+        output.push(line);
+        let next_is_text = lines.get(index + 1).is_some_and(|next| !next.is_empty());
+        if !line.is_empty() && next_is_text {
+            output.push("");
+        }
+    }
+    (output.join("\n"), positions)
+}
+
+// 落とした空行の位置は次に残る行の位置にする
+fn remove_blank_lines(lines: &[String]) -> (String, Vec<usize>) {
+    let mut output: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut positions = Vec::with_capacity(lines.len());
+    let mut previous_blank = false;
+    for (index, line) in lines.iter().enumerate() {
+        positions.push(output.len());
+        // 空行の並びの最初の一行を落とす（文書の先頭と末尾は除く）
+        let first_blank = line.is_empty() && !previous_blank && index > 0;
+        let at_end = lines[index..].iter().all(|line| line.is_empty());
+        if !first_blank || at_end {
+            output.push(line);
+        }
+        previous_blank = line.is_empty();
+    }
+    (output.join("\n"), positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_narou(text: &str, spacing: LineSpacing) -> Conversion {
+        convert(text, Dialect::Kakuyomu, Dialect::Narou, spacing)
+    }
+
+    #[test]
+    fn keeps_final_newline_state() {
+        assert_eq!(to_narou("本文", LineSpacing::Keep).text, "本文");
+        assert_eq!(to_narou("本文\n", LineSpacing::Keep).text, "本文\n");
+        assert_eq!(to_narou("一\n二", LineSpacing::Add).text, "一\n\n二");
+        assert_eq!(to_narou("一\n\n二\n", LineSpacing::Remove).text, "一\n二\n");
+    }
+
+    #[test]
+    fn converts_emphasis_and_ruby() {
+        let narou = to_narou("《《強調》》と｜漢字《かんじ》", LineSpacing::Keep);
+        assert_eq!(narou.text, "｜強《・》｜調《・》と｜漢字《かんじ》");
+        assert!(narou.issues.is_empty());
+
+        let kakuyomu = convert(
+            &narou.text,
+            Dialect::Narou,
+            Dialect::Kakuyomu,
+            LineSpacing::Keep,
+        );
+        assert_eq!(kakuyomu.text, "《《強調》》と｜漢字《かんじ》");
+
+        let plain = convert(
+            "《《強調》》と｜漢字《かんじ》",
+            Dialect::Kakuyomu,
+            Dialect::Plain,
+            LineSpacing::Keep,
+        );
+        assert_eq!(plain.text, "強調と漢字");
+    }
+
+    #[test]
+    fn reports_issues_at_output_lines() {
+        let conversion = to_narou("一\n二\n漢字(かんじ)", LineSpacing::Add);
+        assert_eq!(conversion.issues.len(), 1);
+        assert_eq!(conversion.issues[0].line, 5);
+
+        let conversion = to_narou("一\n\n\n漢字(かんじ)", LineSpacing::Remove);
+        assert_eq!(conversion.issues[0].line, 3);
+    }
+}
@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+// 傍点の記法 "《《強調》》"（範囲は行内のバイト位置）
+pub struct Emphasis {
+    pub range: Range<usize>,
+    pub text: Range<usize>,
+}
+
+const OPEN: &str = "《《";
+const CLOSE: &str = "》》";
+
+pub fn find_emphasis(line: &str) -> Vec<Emphasis> {
+    let mut marks = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = line[offset..].find(OPEN) {
+        let start = offset + open;
+        let text_start = start + OPEN.len();
+        let Some(close) = find_close(line, text_start) else {
+            break;
+        };
+        let text = text_start..close;
+        let end = text.end + CLOSE.len();
+        if !text.is_empty() {
+            marks.push(Emphasis {
+                range: start..end,
+                text,
+            });
+        }
+        offset = end;
+    }
+    marks
+}
+
+// 閉じる "》》" の位置（中のルビ "｜漢字《かんじ》" の "》" では閉じない）
+fn find_close(line: &str, from: usize) -> Option<usize> {
+    let mut position = from;
+    loop {
+        let rest = &line[position..];
+        let close = rest.find(CLOSE)?;
+        match rest.find('《') {
+            Some(ruby) if ruby < close && !rest[ruby..].starts_with(OPEN) => {
+                let ruby_start = position + ruby + '《'.len_utf8();
+                position = ruby_start + line[ruby_start..].find('》')? + '》'.len_utf8();
+            }
+            _ => return Some(position + close),
+        }
+    }
+}
+
+// 記法を取り除いた文字列
+pub fn strip_markup(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut last = 0;
+    for emphasis in find_emphasis(line) {
+        stripped.push_str(&line[last..emphasis.range.start]);
+        stripped.push_str(&line[emphasis.text]);
+        last = emphasis.range.end;
+    }
+    stripped.push_str(&line[last..]);
+    stripped
+}
+
+pub fn wrap(text: &str) -> String {
+    format!("{}{}{}", OPEN, text, CLOSE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_plain_emphasis() {
+        let marks = find_emphasis("前《《強調》》後《《二つ目》》");
+        assert_eq!(marks.len(), 2);
+        assert_eq!(strip_markup("前《《強調》》後"), "前強調後");
+    }
+
+    #[test]
+    fn skips_ruby_inside_emphasis() {
+        let line = "《《｜漢字《かんじ》》》です";
+        let marks = find_emphasis(line);
+        assert_eq!(marks.len(), 1);
+        assert_eq!(&line[marks[0].text.clone()], "｜漢字《かんじ》");
+        assert_eq!(&line[marks[0].range.end..], "です");
+        assert_eq!(strip_markup(line), "｜漢字《かんじ》です");
+    }
+
+    #[test]
+    fn ignores_unclosed_emphasis() {
+        assert!(find_emphasis("《《閉じていない").is_empty());
+        assert!(find_emphasis("《《｜漢字《かんじ").is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::document;
+use crate::file_types::{file_type_for, EditorMode};
+use crate::text::stats::count;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 400 字詰め原稿用紙（20 字 × 20 行）
+pub const COLUMNS: usize = 20;
+pub const ROWS: usize = 20;
+
+// 行頭に置かない文字（前の行の末尾にぶら下げる）
+const LINE_START_PROHIBITED: &str =
+    "、。，．,.）」』】〕〉》］｝〙〗!?！？‼⁇⁈⁉ゝゞヽヾーぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶ・：；…‥";
+
+// 行末に置かない文字（次の行の先頭へ送る）
+const LINE_END_PROHIBITED: &str = "（「『【〔〈《［｛〘〖";
+
+pub fn is_line_start_prohibited(c: char) -> bool {
+    LINE_START_PROHIBITED.contains(c)
+}
+
+pub fn is_line_end_prohibited(c: char) -> bool {
+    LINE_END_PROHIBITED.contains(c)
+}
+
+// 一段落（改行を含まない一行）が原稿用紙で何行になるか
+pub fn paragraph_lines(paragraph: &str) -> usize {
+    // 半角文字は 1 マスに 2 文字入るので、半マス単位で数える
+    let capacity = COLUMNS * 2;
+    let mut lines = 1;
+    let mut used = 0;
+
+    // 字下げのない段落は行頭を一マス空ける（会話文の括弧は除く）
+    match paragraph.chars().next() {
+        None => return 1,
+        Some(first) if first == '　' || is_line_end_prohibited(first) => {}
+        Some(_) => used = 2,
+    }
+
+    let mut chars = paragraph.chars().peekable();
+    while let Some(c) = chars.next() {
+        let width = if c.is_ascii() { 1 } else { 2 };
+        if used + width > capacity {
+            if used >= capacity && is_line_start_prohibited(c) {
+                continue;
+            }
+            lines += 1;
+            used = 0;
+        } else if used + width == capacity && is_line_end_prohibited(c) && chars.peek().is_some() {
+            lines += 1;
+            used = 0;
+        }
+        used += width;
+    }
+    lines
+}
+
+pub fn pages_for_lines(lines: usize) -> usize {
+    lines.div_ceil(ROWS)
+}
+
+// 原稿用紙換算の集計（ファイルごと）
+pub struct ManuscriptEntry {
+    pub path: PathBuf,
+    pub chars: usize,
+    pub pages: usize,
+}
+
+// ファイル・フォルダの原稿用紙換算（ファイルごとに新しい用紙から書き始める）
+pub struct ManuscriptReport {
+    pub entries: Vec<ManuscriptEntry>,
+}
+
+impl ManuscriptReport {
+    pub fn new(paths: &[PathBuf], exclude_markup: bool) -> Self {
+        let mut files = Vec::new();
+        for path in paths {
+            collect_text_files(path, &mut files);
+        }
+
+        let entries = files
+            .into_iter()
+            .filter_map(|path| {
+                let text = document::load(&path).ok()?.text;
+                let counts = count(&text).counted(exclude_markup);
+                Some(ManuscriptEntry {
+                    path,
+                    chars: counts.visible_chars(),
+                    pages: counts.manuscript_pages(),
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    pub fn total_chars(&self) -> usize {
+        self.entries.iter().map(|entry| entry.chars).sum()
+    }
+
+    pub fn total_pages(&self) -> usize {
+        self.entries.iter().map(|entry| entry.pages).sum()
+    }
+}
+
+// 隠しファイルとリンク先のフォルダは対象外
+pub fn collect_text_files(path: &Path, files: &mut Vec<PathBuf>) {
+    let is_hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    if is_hidden {
+        return;
+    }
+    if path.is_dir() {
+        if path.is_symlink() {
+            return;
+        }
+        let mut children: Vec<PathBuf> = fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        children.sort();
+        for child in children {
+            collect_text_files(&child, files);
+        }
+    } else if file_type_for(path).is_some_and(|file_type| {
+        matches!(file_type.mode, EditorMode::PlainText | EditorMode::Markdown)
+    }) {
+        files.push(path.to_path_buf());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indented(body: &str) -> String {
+        format!("　{}", body)
+    }
+
+    #[test]
+    fn counts_full_lines() {
+        assert_eq!(paragraph_lines(""), 1);
+        assert_eq!(paragraph_lines(&indented(&"字".repeat(19))), 1);
+        assert_eq!(paragraph_lines(&indented(&"字".repeat(20))), 2);
+        // 字下げのない段落は行頭を一マス空ける
+        assert_eq!(paragraph_lines(&"字".repeat(19)), 1);
+        assert_eq!(paragraph_lines(&"字".repeat(20)), 2);
+        // 会話文は行頭から書く
+        assert_eq!(paragraph_lines(&format!("「{}", "字".repeat(19))), 1);
+        // 半角文字は一マスに二文字
+        assert_eq!(paragraph_lines(&indented(&"a".repeat(38))), 1);
+        assert_eq!(paragraph_lines(&indented(&"a".repeat(39))), 2);
+    }
+
+    #[test]
+    fn applies_kinsoku() {
+        // 行頭に来る句読点・閉じ括弧は前の行にぶら下げる
+        assert_eq!(
+            paragraph_lines(&indented(&format!("{}。", "字".repeat(19)))),
+            1
+        );
+        assert_eq!(
+            paragraph_lines(&indented(&format!("{}。」", "字".repeat(19)))),
+            1
+        );
+        // 行末に来る開き括弧は次の行へ送る
+        assert_eq!(
+            paragraph_lines(&indented(&format!("{}「あ", "字".repeat(18)))),
+            2
+        );
+        assert_eq!(
+            paragraph_lines(&indented(&format!("{}「", "字".repeat(18)))),
+            1
+        );
+    }
+
+    #[test]
+    fn rounds_pages_up() {
+        assert_eq!(pages_for_lines(0), 0);
+        assert_eq!(pages_for_lines(20), 1);
+        assert_eq!(pages_for_lines(21), 2);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::text::emphasis::find_emphasis;
+use crate::text::ruby::find_rubies;
+
+// ルビは <ruby>、傍点は text-emphasis で表示する HTML に変換する
+pub fn to_html(title: &str, text: &str, vertical: bool) -> String {
+    let writing_mode = if vertical {
+        "vertical-rl"
+    } else {
+        "horizontal-tb"
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n\
+         body {{ writing-mode: {}; line-height: 1.8; }}\n\
+         p {{ margin: 0; }}\n\
+         em.emphasis {{ font-style: normal; -webkit-text-emphasis: filled sesame; text-emphasis: filled sesame; }}\n\
+         </style>\n</head>\n<body>\n",
+        escape(title),
+        writing_mode
+    );
+    for line in text.split('\n') {
+        if line.is_empty() {
+            html.push_str("<p><br></p>\n");
+        } else {
+            html.push_str(&format!("<p>{}</p>\n", line_to_html(line)));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn line_to_html(line: &str) -> String {
+    let rubies = find_rubies(line);
+    let emphasis = find_emphasis(line);
+    let mut html = String::new();
+
+    let mut byte = 0;
+    while byte < line.len() {
+        if let Some(ruby) = rubies.iter().find(|ruby| ruby.range.start == byte) {
+            html.push_str(&format!(
+                "<ruby>{}<rt>{}</rt></ruby>",
+                escape(&line[ruby.base.clone()]),
+                escape(&line[ruby.ruby.clone()])
+            ));
+            byte = ruby.range.end;
+        } else if let Some(mark) = emphasis.iter().find(|mark| mark.range.start == byte) {
+            html.push_str("<em class=\"emphasis\">");
+            byte = mark.text.start;
+        } else if let Some(mark) = emphasis.iter().find(|mark| mark.text.end == byte) {
+            html.push_str("</em>");
+            byte = mark.range.end;
+        } else {
+            let c = line[byte..].chars().next().unwrap();
+            html.push_str(&escape(&c.to_string()));
+            byte += c.len_utf8();
+        }
+    }
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
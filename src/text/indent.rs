@@ -0,0 +1,66 @@
+// 段落の字下げと括弧の対応（原稿の書式の補助）
+pub const INDENT: char = '　';
+
+// 自動で閉じる括弧
+pub const BRACKET_PAIRS: [(char, char); 4] =
+    [('「', '」'), ('『', '』'), ('（', '）'), ('【', '】')];
+
+pub struct IndentRules {
+    // 改行で新しい段落を全角スペースで字下げする（原稿用に有効にする。既定は無効）
+    pub auto_indent: bool,
+    // 開き括弧の入力で閉じ括弧を補う
+    pub auto_pair: bool,
+    // この文字で始まる段落は字下げしない
+    pub exempt: String,
+}
+
+impl Default for IndentRules {
+    fn default() -> Self {
+        Self {
+            auto_indent: false,
+            auto_pair: true,
+            exempt: "「『（【〈《―…".to_string(),
+        }
+    }
+}
+
+impl IndentRules {
+    pub fn is_exempt(&self, c: char) -> bool {
+        self.exempt.contains(c)
+    }
+}
+
+pub fn closing_bracket(open: char) -> Option<char> {
+    BRACKET_PAIRS
+        .iter()
+        .find(|&&(o, _)| o == open)
+        .map(|&(_, close)| close)
+}
+
+pub fn is_closing_bracket(c: char) -> bool {
+    BRACKET_PAIRS.iter().any(|&(_, close)| close == c)
+}
+
+// 見出し・改ページなど、段落ではない行
+fn is_structural(line: &str) -> bool {
+    line.starts_with('#') || line.trim() == "---"
+}
+
+// 段落の先頭を規則どおりにそろえる（空白だけの行は空行にする）
+pub fn normalize_indentation(text: &str, rules: &IndentRules) -> String {
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            if is_structural(line) {
+                return line.to_string();
+            }
+            let body = line.trim_start_matches([' ', INDENT, '\t']);
+            match body.chars().next() {
+                None => String::new(),
+                Some(first) if rules.is_exempt(first) => body.to_string(),
+                Some(_) => format!("{}{}", INDENT, body),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
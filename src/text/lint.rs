@@ -0,0 +1,370 @@
+use crate::text::{emphasis, ruby};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+// 校正の規則（ワークスペースごとに無効にできる）
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    RaNuki,
+    DoubledParticle,
+    LongSentence,
+    PeriodBeforeBracket,
+    UnbalancedBrackets,
+    MixedPunctuation,
+    UnpairedDashes,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 7] = [
+        Rule::RaNuki,
+        Rule::DoubledParticle,
+        Rule::LongSentence,
+        Rule::PeriodBeforeBracket,
+        Rule::UnbalancedBrackets,
+        Rule::MixedPunctuation,
+        Rule::UnpairedDashes,
+    ];
+
+    // 設定ファイルに保存する名前
+    pub fn key(self) -> &'static str {
+        match self {
+            Rule::RaNuki => "ra_nuki",
+            Rule::DoubledParticle => "doubled_particle",
+            Rule::LongSentence => "long_sentence",
+            Rule::PeriodBeforeBracket => "period_before_bracket",
+            Rule::UnbalancedBrackets => "unbalanced_brackets",
+            Rule::MixedPunctuation => "mixed_punctuation",
+            Rule::UnpairedDashes => "unpaired_dashes",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Rule::RaNuki => "ら抜き言葉",
+            Rule::DoubledParticle => "Doubled particles",
+            Rule::LongSentence => "Long sentences",
+            Rule::PeriodBeforeBracket => "。 before a closing 」",
+            Rule::UnbalancedBrackets => "Unbalanced 「」『』",
+            Rule::MixedPunctuation => "Mixed half-width and full-width punctuation",
+            Rule::UnpairedDashes => "…/― not used in pairs",
+        }
+    }
+}
+
+pub struct LintConfig {
+    pub disabled: BTreeSet<Rule>,
+    // 一文の最大の文字数（記法を除く）
+    pub max_sentence_length: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            disabled: BTreeSet::new(),
+            max_sentence_length: 100,
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn is_enabled(&self, rule: Rule) -> bool {
+        !self.disabled.contains(&rule)
+    }
+}
+
+// 指摘（range は文書全体のバイト位置、chars は文字の位置、行と列は 1 始まり）
+pub struct Diagnostic {
+    pub range: Range<usize>,
+    pub chars: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub rule: Rule,
+    pub message: String,
+}
+
+pub fn lint(text: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        config,
+        patterns: Patterns::get(),
+        diagnostics: Vec::new(),
+    };
+    let mut offset = 0;
+    for line in text.split('\n') {
+        linter.line(line, offset);
+        offset += line.len() + 1;
+    }
+    if config.is_enabled(Rule::MixedPunctuation) {
+        linter.mixed_punctuation(text);
+    }
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+    locate(text, &mut diagnostics);
+    diagnostics
+}
+
+// 一度の走査で文字の位置と行・列を求める（diagnostics は開始位置の順）
+fn locate(text: &str, diagnostics: &mut [Diagnostic]) {
+    let mut boundaries: Vec<usize> = diagnostics
+        .iter()
+        .flat_map(|diagnostic| [diagnostic.range.start, diagnostic.range.end])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut positions = Vec::with_capacity(boundaries.len());
+    let (mut char_index, mut line, mut column) = (0, 1, 1);
+    let mut next = boundaries.iter().peekable();
+    for (index, c) in text.char_indices().chain([(text.len(), '\n')]) {
+        while next.peek().is_some_and(|&&boundary| boundary <= index) {
+            positions.push((*next.next().unwrap(), char_index, line, column));
+        }
+        char_index += 1;
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+
+    let position = |byte: usize| {
+        let found = positions.partition_point(|&(boundary, ..)| boundary < byte);
+        positions[found]
+    };
+    for diagnostic in diagnostics {
+        let (_, start, line, column) = position(diagnostic.range.start);
+        let (_, end, ..) = position(diagnostic.range.end);
+        diagnostic.chars = start..end;
+        diagnostic.line = line;
+        diagnostic.column = column;
+    }
+}
+
+struct Patterns {
+    ra_nuki: Regex,
+    doubled_particle: Regex,
+    period_before_bracket: Regex,
+    dashes: Regex,
+}
+
+impl Patterns {
+    // 正規表現は最初の校正で一度だけ作る
+    fn get() -> &'static Self {
+        static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+        PATTERNS.get_or_init(Self::new)
+    }
+
+    fn new() -> Self {
+        Self {
+            // 一段動詞・カ変動詞の可能形で「ら」が抜けたもの（見れる、食べれる、来れない など）
+            ra_nuki: Regex::new(
+                r"(?:[見来着寝出居似煮]|\p{Han}[いきぎじちにびみりえけげせぜてでねべめれ])れ(?:る|ない|ます|ません|た|て)",
+            )
+            .unwrap(),
+            // 漢字・カタカナ・閉じ括弧の直後に同じ助詞が続くもの（「東京のの」など）
+            doubled_particle: Regex::new(r"[\p{Han}\p{Katakana}」』）]([がをにでとへもはの])([がをにでとへもはの])")
+                .unwrap(),
+            period_before_bracket: Regex::new(r"。[」』]").unwrap(),
+            dashes: Regex::new(r"…+|―+").unwrap(),
+        }
+    }
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    patterns: &'static Patterns,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: Rule, range: Range<usize>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            range,
+            chars: 0..0,
+            line: 0,
+            column: 0,
+            rule,
+            message,
+        });
+    }
+
+    fn line(&mut self, line: &str, offset: usize) {
+        let shift = |range: Range<usize>| offset + range.start..offset + range.end;
+        let enabled = |rule| self.config.is_enabled(rule);
+
+        if enabled(Rule::RaNuki) {
+            let found: Vec<_> = self.patterns.ra_nuki.find_iter(line).collect();
+            for found in found {
+                let word = found.as_str();
+                let split = word.find('れ').unwrap_or(0);
+                let message = format!(
+                    "ら抜き言葉: 「{}」→「{}ら{}」",
+                    word,
+                    &word[..split],
+                    &word[split..]
+                );
+                self.report(Rule::RaNuki, shift(found.range()), message);
+            }
+        }
+        if enabled(Rule::DoubledParticle) {
+            let found: Vec<_> = self
+                .patterns
+                .doubled_particle
+                .captures_iter(line)
+                .filter(|captures| captures[1] == captures[2])
+                .map(|captures| {
+                    let first = captures.get(1).unwrap();
+                    (
+                        first.start()..captures.get(2).unwrap().end(),
+                        first.as_str().to_string(),
+                    )
+                })
+                .collect();
+            for (range, particle) in found {
+                let message = format!("Doubled particle 「{}」", particle.repeat(2));
+                self.report(Rule::DoubledParticle, shift(range), message);
+            }
+        }
+        if enabled(Rule::PeriodBeforeBracket) {
+            let found: Vec<_> = self
+                .patterns
+                .period_before_bracket
+                .find_iter(line)
+                .map(|found| found.start()..found.start() + '。'.len_utf8())
+                .collect();
+            for range in found {
+                let message = "Remove 。 before a closing bracket".to_string();
+                self.report(Rule::PeriodBeforeBracket, shift(range), message);
+            }
+        }
+        if enabled(Rule::UnpairedDashes) {
+            let found: Vec<_> = self
+                .patterns
+                .dashes
+                .find_iter(line)
+                .filter(|found| found.as_str().chars().count() % 2 == 1)
+                .map(|found| (found.range(), found.as_str().chars().next().unwrap()))
+                .collect();
+            for (range, mark) in found {
+                let message = format!("Use 「{}」 in pairs", mark.to_string().repeat(2));
+                self.report(Rule::UnpairedDashes, shift(range), message);
+            }
+        }
+        if enabled(Rule::UnbalancedBrackets) {
+            self.unbalanced_brackets(line, offset);
+        }
+        if enabled(Rule::LongSentence) {
+            self.long_sentences(line, offset);
+        }
+    }
+
+    // 括弧の対応は段落（行）の中で調べる
+    fn unbalanced_brackets(&mut self, line: &str, offset: usize) {
+        let mut open: Vec<(usize, char)> = Vec::new();
+        for (index, c) in line.char_indices() {
+            match c {
+                '「' | '『' => open.push((index, c)),
+                '」' | '』' => {
+                    let expected = if c == '」' { '「' } else { '『' };
+                    match open.last() {
+                        Some(&(_, last)) if last == expected => {
+                            open.pop();
+                        }
+                        _ => {
+                            let range = offset + index..offset + index + c.len_utf8();
+                            let message = format!("Unmatched closing 「{}」", c);
+                            self.report(Rule::UnbalancedBrackets, range, message);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (index, c) in open {
+            let range = offset + index..offset + index + c.len_utf8();
+            let message = format!("Unclosed 「{}」", c);
+            self.report(Rule::UnbalancedBrackets, range, message);
+        }
+    }
+
+    // 句点・感嘆符・疑問符で区切った文の長さ（括弧の中の句点では区切らない）
+    fn long_sentences(&mut self, line: &str, offset: usize) {
+        let max = self.config.max_sentence_length;
+        let mut start = 0;
+        let mut depth = 0usize;
+        let mut sentences = Vec::new();
+        for (index, c) in line.char_indices() {
+            match c {
+                '「' | '『' | '（' => depth += 1,
+                '」' | '』' | '）' => depth = depth.saturating_sub(1),
+                '。' | '！' | '？' if depth == 0 => {
+                    let end = index + c.len_utf8();
+                    sentences.push(start..end);
+                    start = end;
+                }
+                _ => {}
+            }
+        }
+        sentences.push(start..line.len());
+
+        for range in sentences {
+            let sentence = &line[range.clone()];
+            let length = ruby::strip_markup(&emphasis::strip_markup(sentence.trim()))
+                .chars()
+                .count();
+            if length > max {
+                let message = format!("Sentence is {} characters long (max {})", length, max);
+                self.report(
+                    Rule::LongSentence,
+                    offset + range.start..offset + range.end,
+                    message,
+                );
+            }
+        }
+    }
+
+    // 同じ記号の半角と全角が混ざっていれば、少ない方を指摘する
+    // 英数字の直後の半角記号は英文とみなして数えない
+    fn mixed_punctuation(&mut self, text: &str) {
+        const PAIRS: [(char, char); 6] = [
+            ('!', '！'),
+            ('?', '？'),
+            (',', '，'),
+            (':', '：'),
+            (';', '；'),
+            ('(', '（'),
+        ];
+        for (half, full) in PAIRS {
+            let mut halves = Vec::new();
+            let mut fulls = Vec::new();
+            let mut previous = '\n';
+            for (index, c) in text.char_indices() {
+                if c == half && !previous.is_ascii_alphanumeric() && previous != ' ' {
+                    halves.push(index);
+                } else if c == full {
+                    fulls.push(index);
+                }
+                previous = c;
+            }
+            if halves.is_empty() || fulls.is_empty() {
+                continue;
+            }
+            let (minority, mark, preferred) = if halves.len() <= fulls.len() {
+                (halves, half, full)
+            } else {
+                (fulls, full, half)
+            };
+            for index in minority {
+                let message = format!("Mixed punctuation: 「{}」 (mostly 「{}」)", mark, preferred);
+                let range = index..index + mark.len_utf8();
+                self.report(Rule::MixedPunctuation, range, message);
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod aozora;
+pub mod columns;
+pub mod convert;
+pub mod dialect;
+pub mod emphasis;
+pub mod genko;
+pub mod html;
+pub mod indent;
+pub mod lint;
+pub mod ruby;
+pub mod stats;
+pub mod variants;
+pub mod vertical;
+pub mod whitespace;
@@ -0,0 +1,83 @@
+use crate::text::stats::is_kanji;
+use std::ops::Range;
+
+// ルビの記法（範囲は行内のバイト位置）
+// "｜親文字《ルビ》" または "漢字《ルビ》"（直前の漢字の並びが親文字）
+pub struct Ruby {
+    pub range: Range<usize>,
+    pub base: Range<usize>,
+    pub ruby: Range<usize>,
+}
+
+pub fn find_rubies(line: &str) -> Vec<Ruby> {
+    let mut rubies = Vec::new();
+    // 親文字の開始位置の指定（"｜" の直後）
+    let mut bar: Option<(usize, usize)> = None;
+    // 直前のルビの終わり（親文字はここより前にさかのぼらない）
+    let mut plain_start = 0;
+
+    let mut chars = line.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '｜' | '|' => bar = Some((index, index + c.len_utf8())),
+            // "《《" は傍点の記法なのでルビとして扱わない
+            '《' if chars.peek().is_some_and(|&(_, next)| next == '《') => {
+                chars.next();
+                bar = None;
+            }
+            '《' => {
+                let ruby_start = index + c.len_utf8();
+                let Some(ruby_len) = line[ruby_start..].find('》') else {
+                    continue;
+                };
+                let ruby = ruby_start..ruby_start + ruby_len;
+                let (start, base) = match bar.take() {
+                    Some((bar_start, base_start)) => (bar_start, base_start..index),
+                    None => {
+                        let base_start = line[plain_start..index]
+                            .char_indices()
+                            .rev()
+                            .take_while(|&(_, c)| is_kanji(c))
+                            .last()
+                            .map_or(index, |(i, _)| plain_start + i);
+                        (base_start, base_start..index)
+                    }
+                };
+                if base.is_empty() || ruby.is_empty() {
+                    continue;
+                }
+
+                let end = ruby.end + '》'.len_utf8();
+                rubies.push(Ruby {
+                    range: start..end,
+                    base,
+                    ruby,
+                });
+                plain_start = end;
+                while chars.peek().is_some_and(|&(i, _)| i < end) {
+                    chars.next();
+                }
+            }
+            _ => {}
+        }
+    }
+    rubies
+}
+
+// 記法を取り除いた文字列（親文字だけを残す）
+pub fn strip_markup(line: &str) -> String {
+    let mut stripped = String::with_capacity(line.len());
+    let mut last = 0;
+    for ruby in find_rubies(line) {
+        stripped.push_str(&line[last..ruby.range.start]);
+        stripped.push_str(&line[ruby.base]);
+        last = ruby.range.end;
+    }
+    stripped.push_str(&line[last..]);
+    stripped
+}
+
+// 親文字を選択範囲の文字列とするルビの記法
+pub fn wrap(base: &str, reading: &str) -> String {
+    format!("｜{}《{}》", base, reading)
+}
@@ -0,0 +1,296 @@
+use crate::text::genko::{pages_for_lines, paragraph_lines};
+use crate::text::{emphasis, ruby};
+use eframe::egui::TextBuffer;
+use std::ops::{AddAssign, Range, SubAssign};
+
+// 文字数などの集計（行ごとに持ち、足し引きで全体を更新する）
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Counts {
+    pub chars: usize,
+    pub whitespace: usize,
+    pub line_breaks: usize,
+    pub kanji: usize,
+    pub hiragana: usize,
+    pub katakana: usize,
+    pub ascii: usize,
+    pub paragraphs: usize,
+    pub manuscript_lines: usize,
+    // ルビ・傍点の記法の文字数（chars などには含めない）
+    pub markup: usize,
+}
+
+impl Counts {
+    // 記法を文字数に含める場合は記法の分を加える
+    pub fn counted(mut self, exclude_markup: bool) -> Self {
+        if !exclude_markup {
+            self.chars += self.markup;
+        }
+        self.markup = 0;
+        self
+    }
+
+    // 改行を含む文字数
+    pub fn total_chars(&self) -> usize {
+        self.chars + self.line_breaks
+    }
+
+    // 空白・改行を除いた文字数
+    pub fn visible_chars(&self) -> usize {
+        self.chars - self.whitespace
+    }
+
+    // 400 字詰め原稿用紙の枚数
+    pub fn manuscript_pages(&self) -> usize {
+        pages_for_lines(self.manuscript_lines)
+    }
+
+    // 空白・改行を除いた文字数に対する割合（%）
+    pub fn ratio(&self, count: usize) -> f32 {
+        match self.visible_chars() {
+            0 => 0.0,
+            visible => count as f32 * 100.0 / visible as f32,
+        }
+    }
+}
+
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Self) {
+        self.chars += other.chars;
+        self.whitespace += other.whitespace;
+        self.line_breaks += other.line_breaks;
+        self.kanji += other.kanji;
+        self.hiragana += other.hiragana;
+        self.katakana += other.katakana;
+        self.ascii += other.ascii;
+        self.paragraphs += other.paragraphs;
+        self.manuscript_lines += other.manuscript_lines;
+        self.markup += other.markup;
+    }
+}
+
+impl SubAssign for Counts {
+    fn sub_assign(&mut self, other: Self) {
+        self.chars -= other.chars;
+        self.whitespace -= other.whitespace;
+        self.line_breaks -= other.line_breaks;
+        self.kanji -= other.kanji;
+        self.hiragana -= other.hiragana;
+        self.katakana -= other.katakana;
+        self.ascii -= other.ascii;
+        self.paragraphs -= other.paragraphs;
+        self.manuscript_lines -= other.manuscript_lines;
+        self.markup -= other.markup;
+    }
+}
+
+// 任意の範囲の集計（選択範囲など）
+pub fn count(text: &str) -> Counts {
+    let mut counts = Counts::default();
+    for line in text.split('\n') {
+        counts += count_line(line);
+    }
+    counts.line_breaks = text.matches('\n').count();
+    // 末尾の改行の後は原稿用紙の行として数えない
+    if counts.line_breaks > 0 && text.ends_with('\n') {
+        counts.manuscript_lines -= 1;
+    }
+    counts
+}
+
+// 改行を含まない一行の集計（空白以外の文字があれば一段落）
+fn count_line(line: &str) -> Counts {
+    let stripped = emphasis::strip_markup(&ruby::strip_markup(line));
+    let mut counts = Counts {
+        markup: line.chars().count() - stripped.chars().count(),
+        ..Counts::default()
+    };
+    for c in stripped.chars() {
+        counts.chars += 1;
+        if c.is_whitespace() {
+            counts.whitespace += 1;
+        } else if is_kanji(c) {
+            counts.kanji += 1;
+        } else if is_hiragana(c) {
+            counts.hiragana += 1;
+        } else if is_katakana(c) {
+            counts.katakana += 1;
+        } else if c.is_ascii() {
+            counts.ascii += 1;
+        }
+    }
+    counts.paragraphs = usize::from(counts.chars > counts.whitespace);
+    // ルビ・傍点は原稿用紙のマスを使わない
+    counts.manuscript_lines = paragraph_lines(&stripped);
+    counts
+}
+
+pub fn is_kanji(c: char) -> bool {
+    matches!(c,
+        '\u{4E00}'..='\u{9FFF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FFFF}'
+        | '々' | '〆' | '〇')
+}
+
+pub fn is_hiragana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{309F}')
+}
+
+pub fn is_katakana(c: char) -> bool {
+    matches!(c, '\u{30A0}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+// 文書全体の集計（編集された行だけを数え直す）
+pub struct DocumentStats {
+    lines: Vec<Counts>,
+    total: Counts,
+}
+
+impl Default for DocumentStats {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl DocumentStats {
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<Counts> = text.split('\n').map(count_line).collect();
+        let mut total = Counts::default();
+        for line in &lines {
+            total += *line;
+        }
+        total.line_breaks = lines.len() - 1;
+        Self { lines, total }
+    }
+
+    // 論理行の数（改行の数 + 1）
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn total(&self) -> Counts {
+        let mut total = self.total;
+        if self.lines.len() > 1 && self.lines.last().is_some_and(|line| line.chars == 0) {
+            total.manuscript_lines -= 1;
+        }
+        total
+    }
+
+    // old の byte_range が inserted に置き換えられたときの更新
+    fn replace(&mut self, old: &str, byte_range: Range<usize>, inserted: &str) {
+        let first_line = old[..byte_range.start].matches('\n').count();
+        let last_line = first_line + old[byte_range.clone()].matches('\n').count();
+
+        // 編集を含む行の範囲を編集後の文字列で数え直す
+        let line_start = old[..byte_range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = old[byte_range.end..]
+            .find('\n')
+            .map_or(old.len(), |i| byte_range.end + i);
+        let edited = format!(
+            "{}{}{}",
+            &old[line_start..byte_range.start],
+            inserted,
+            &old[byte_range.end..line_end]
+        );
+        let new_lines: Vec<Counts> = edited.split('\n').map(count_line).collect();
+
+        for line in &self.lines[first_line..=last_line] {
+            self.total -= *line;
+        }
+        for line in &new_lines {
+            self.total += *line;
+        }
+        self.lines.splice(first_line..=last_line, new_lines);
+        self.total.line_breaks = self.lines.len() - 1;
+    }
+}
+
+// 編集内容を集計に反映しながら文字列を変更する TextEdit 用のバッファ
+pub struct TrackedText<'a> {
+    pub text: &'a mut String,
+    pub stats: &'a mut DocumentStats,
+}
+
+impl TextBuffer for TrackedText<'_> {
+    fn is_mutable(&self) -> bool {
+        true
+    }
+
+    fn as_str(&self) -> &str {
+        self.text
+    }
+
+    fn insert_text(&mut self, text: &str, char_index: usize) -> usize {
+        let byte_index = self.byte_index_from_char_index(char_index);
+        self.stats.replace(self.text, byte_index..byte_index, text);
+        self.text.insert_text(text, char_index)
+    }
+
+    fn delete_char_range(&mut self, char_range: Range<usize>) {
+        let start = self.byte_index_from_char_index(char_range.start);
+        let end = self.byte_index_from_char_index(char_range.end);
+        self.stats.replace(self.text, start..end, "");
+        self.text.delete_char_range(char_range);
+    }
+
+    fn replace_with(&mut self, text: &str) {
+        self.text.replace_with(text);
+        *self.stats = DocumentStats::new(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 差分で更新した集計が、数え直した集計と一致するか
+    fn assert_tracked(text: &str, edit: impl FnOnce(&mut TrackedText)) {
+        let mut text = text.to_string();
+        let mut stats = DocumentStats::new(&text);
+        edit(&mut TrackedText {
+            text: &mut text,
+            stats: &mut stats,
+        });
+        let recounted = DocumentStats::new(&text);
+        assert!(stats.total() == recounted.total(), "{:?}", text);
+        assert_eq!(stats.line_count(), recounted.line_count());
+    }
+
+    #[test]
+    fn tracks_edits_like_a_recount() {
+        let text =
+            "　吾輩は猫である。\n名前はまだ無い。\n\n｜漢字《かんじ》と《《傍点》》\nABC 123";
+        assert_tracked(text, |buffer| {
+            buffer.insert_text("追加", 3);
+        });
+        assert_tracked(text, |buffer| {
+            buffer.insert_text("改行\n\nを含む\n", 5);
+        });
+        assert_tracked(text, |buffer| {
+            buffer.delete_char_range(2..14);
+        });
+        // ルビの記法を途中で壊す
+        assert_tracked(text, |buffer| {
+            buffer.delete_char_range(21..22);
+        });
+        assert_tracked(text, |buffer| {
+            let end = buffer.as_str().chars().count();
+            buffer.delete_char_range(0..end);
+        });
+        assert_tracked("", |buffer| {
+            buffer.insert_text("一\n二\n", 0);
+        });
+    }
+
+    #[test]
+    fn counts_character_classes() {
+        let counts = count("漢字とカナとabc");
+        assert_eq!(counts.chars, 9);
+        assert_eq!(counts.kanji, 2);
+        assert_eq!(counts.hiragana, 2);
+        assert_eq!(counts.katakana, 2);
+        assert_eq!(counts.ascii, 3);
+        assert_eq!(DocumentStats::new("一\n二\n").line_count(), 3);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::document;
+use crate::text::convert::is_kanji_numeral;
+use crate::text::emphasis::find_emphasis;
+use crate::text::ruby::find_rubies;
+use crate::text::stats::is_katakana;
+use crate::workspace::{metadata_dir, read_metadata, write_metadata};
+use regex::Regex;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+// 表記ゆれの辞書（ワークスペースのメタデータに保存する）
+// 1 行に 1 組、カンマ区切りで先頭が推奨する表記。"#" 以降はコメント
+// "!" で始まる語は除外する語で、その中に含まれる表記は数えず置き換えもしない（"!一人前"）
+pub const DICTIONARY_FILE: &str = "variants";
+
+const DEFAULT_DICTIONARY: &str = "\
+# 表記ゆれの辞書（1 行に 1 組、カンマ区切りで先頭が推奨する表記）
+# 「!」で始まる語は除外する語（その語の中の表記はそのままにする）
+できる,出来る
+できない,出来ない
+できた,出来た
+ください,下さい
+ひとり,一人,!一人前,!一人称,!一人暮らし
+ふたり,二人,!二人三脚,!二人称
+わかる,分かる,解る,判る
+わかった,分かった,解った,判った
+ほしい,欲しい
+たくさん,沢山
+すでに,既に
+ちょうど,丁度
+やはり,やっぱり,矢張り
+ようやく,漸く
+おもしろい,面白い
+コンピューター,コンピュータ
+ユーザー,ユーザ
+サーバー,サーバ
+";
+
+pub struct VariantGroup {
+    pub forms: Vec<String>,
+    pub exclusions: Vec<String>,
+    pattern: Regex,
+}
+
+impl VariantGroup {
+    fn new(forms: Vec<String>, exclusions: Vec<String>) -> Option<Self> {
+        // 長い表記を先に試す（"ユーザー" の中の "ユーザ" を数えない）
+        let mut sorted = forms.clone();
+        sorted.sort_by_key(|form| std::cmp::Reverse(form.len()));
+        let alternation: Vec<String> = sorted.iter().map(|form| regex::escape(form)).collect();
+        let pattern = Regex::new(&alternation.join("|")).ok()?;
+        Some(Self {
+            forms,
+            exclusions,
+            pattern,
+        })
+    }
+
+    // 表記の出現位置と表記の番号
+    // ルビ・傍点の記法の中、除外する語の中、長いカタカナ語の一部（"サーバント" の "サーバ"）、
+    // 数の一部（"十一人" "第一人者" の "一人"）は除く
+    pub fn find(&self, text: &str) -> Vec<(Range<usize>, usize)> {
+        let mut skipped = markup_ranges(text);
+        for exclusion in &self.exclusions {
+            skipped.extend(
+                text.match_indices(exclusion.as_str())
+                    .map(|(start, word)| start..start + word.len()),
+            );
+        }
+
+        self.pattern
+            .find_iter(text)
+            .filter(|found| {
+                !skipped
+                    .iter()
+                    .any(|range| range.start < found.end() && found.start() < range.end)
+            })
+            .filter(|found| {
+                let form = found.as_str();
+                let before = text[..found.start()].chars().next_back();
+                let after = text[found.end()..].chars().next();
+                let joins = |edge: Option<char>, neighbor: Option<char>| {
+                    edge.is_some_and(is_word_katakana) && neighbor.is_some_and(is_word_katakana)
+                };
+                let in_number = form.chars().next().is_some_and(is_kanji_numeral)
+                    && before.is_some_and(|c| is_kanji_numeral(c) || c == '第');
+                !joins(form.chars().next(), before)
+                    && !joins(form.chars().next_back(), after)
+                    && !in_number
+            })
+            .filter_map(|found| {
+                let index = self.forms.iter().position(|form| form == found.as_str())?;
+                Some((found.range(), index))
+            })
+            .collect()
+    }
+}
+
+// 語の途中とみなすカタカナ（中黒は語の区切り）
+fn is_word_katakana(c: char) -> bool {
+    is_katakana(c) && c != '・'
+}
+
+// ルビと傍点の記法全体の範囲
+fn markup_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let shift = |range: Range<usize>| range.start + offset..range.end + offset;
+        ranges.extend(find_rubies(line).into_iter().map(|ruby| shift(ruby.range)));
+        ranges.extend(
+            find_emphasis(line)
+                .into_iter()
+                .map(|emphasis| shift(emphasis.range)),
+        );
+        offset += line.len();
+    }
+    ranges
+}
+
+pub struct Dictionary {
+    pub groups: Vec<VariantGroup>,
+}
+
+impl Dictionary {
+    pub fn parse(source: &str) -> Self {
+        let groups = source
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .filter_map(|line| {
+                let (exclusions, forms): (Vec<String>, Vec<String>) = line
+                    .split([',', '，', '、'])
+                    .map(|form| form.trim().to_string())
+                    .filter(|form| !form.is_empty())
+                    .partition(|form| form.starts_with(['!', '！']));
+                if forms.len() < 2 {
+                    return None;
+                }
+                let exclusions = exclusions
+                    .iter()
+                    .map(|word| word.trim_start_matches(['!', '！']).to_string())
+                    .filter(|word| !word.is_empty())
+                    .collect();
+                VariantGroup::new(forms, exclusions)
+            })
+            .collect();
+        Self { groups }
+    }
+
+    // 辞書がなければ既定の辞書を使う
+    pub fn load(root: &Path) -> Self {
+        Self::parse(&read_metadata(root, DICTIONARY_FILE).unwrap_or(DEFAULT_DICTIONARY.into()))
+    }
+
+    // 編集用に辞書のファイルを用意する
+    pub fn ensure_file(root: &Path) -> io::Result<PathBuf> {
+        if read_metadata(root, DICTIONARY_FILE).is_none() {
+            write_metadata(root, DICTIONARY_FILE, DEFAULT_DICTIONARY)?;
+        }
+        Ok(metadata_dir(root).join(DICTIONARY_FILE))
+    }
+}
+
+// 組ごとの表記の出現数（表記は辞書の順）と、出現したファイル
+pub struct VariantReport {
+    pub group: usize,
+    pub counts: Vec<usize>,
+    pub files: Vec<(PathBuf, Vec<usize>)>,
+}
+
+impl VariantReport {
+    // 複数の表記が使われているか
+    pub fn is_mixed(&self) -> bool {
+        self.counts.iter().filter(|&&count| count > 0).count() > 1
+    }
+}
+
+// 複数の表記が混ざっている組だけを返す
+pub fn analyze(files: &[PathBuf], dictionary: &Dictionary) -> Vec<VariantReport> {
+    let mut reports: Vec<VariantReport> = (0..dictionary.groups.len())
+        .map(|group| VariantReport {
+            group,
+            counts: vec![0; dictionary.groups[group].forms.len()],
+            files: Vec::new(),
+        })
+        .collect();
+
+    for path in files {
+        let Ok(loaded) = document::load(path) else {
+            continue;
+        };
+        for (group, report) in dictionary.groups.iter().zip(reports.iter_mut()) {
+            let mut counts = vec![0; group.forms.len()];
+            for (_, index) in group.find(&loaded.text) {
+                counts[index] += 1;
+            }
+            if counts.iter().any(|&count| count > 0) {
+                for (total, count) in report.counts.iter_mut().zip(&counts) {
+                    *total += count;
+                }
+                report.files.push((path.clone(), counts));
+            }
+        }
+    }
+    reports.retain(VariantReport::is_mixed);
+    reports
+}
+
+// 組のすべての表記を target に置き換える（置き換えた数を返す）
+pub fn unify(text: &str, group: &VariantGroup, target: &str) -> (String, usize) {
+    let mut unified = String::with_capacity(text.len());
+    let mut replaced = 0;
+    let mut last = 0;
+    for (range, index) in group.find(text) {
+        if group.forms[index] == target {
+            continue;
+        }
+        unified.push_str(&text[last..range.start]);
+        unified.push_str(target);
+        last = range.end;
+        replaced += 1;
+    }
+    unified.push_str(&text[last..]);
+    (unified, replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unify_default(text: &str, target: &str) -> String {
+        let dictionary = Dictionary::parse(DEFAULT_DICTIONARY);
+        let group = dictionary
+            .groups
+            .iter()
+            .find(|group| group.forms.iter().any(|form| form == target))
+            .unwrap();
+        unify(text, group, target).0
+    }
+
+    #[test]
+    fn leaves_numbers_alone() {
+        assert_eq!(unify_default("十一人と一人", "ひとり"), "十一人とひとり");
+        assert_eq!(unify_default("第一人者", "ひとり"), "第一人者");
+        assert_eq!(unify_default("十二人、二人", "ふたり"), "十二人、ふたり");
+    }
+
+    #[test]
+    fn skips_exclusions_and_markup() {
+        assert_eq!(unify_default("一人前の一人", "ひとり"), "一人前のひとり");
+        assert_eq!(
+            unify_default("｜一人《ひとり》", "ひとり"),
+            "｜一人《ひとり》"
+        );
+        assert_eq!(
+            unify_default("サーバントとサーバ", "サーバー"),
+            "サーバントとサーバー"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::text::emphasis::{find_emphasis, Emphasis};
+use crate::text::genko::{is_line_end_prohibited, is_line_start_prohibited};
+use crate::text::ruby::find_rubies;
+use std::ops::Range;
+
+// 縦書きでの字形の扱い
+#[derive(Clone, Copy, PartialEq)]
+pub enum GlyphKind {
+    Upright,
+    // 横向きの文字を 90 度回転する（括弧・長音・英字など）
+    Rotated,
+    // 句読点はマスの右上に寄せる
+    Punctuation,
+    // 小書きの仮名は少し右上に寄せる
+    Small,
+    // 縦中横（短い数字の並びを横に並べて一マスに入れる）
+    Horizontal,
+}
+
+pub struct Cell {
+    pub text: String,
+    pub kind: GlyphKind,
+    // 文書の先頭からの文字位置
+    pub char_index: usize,
+    // 傍点を付けるか
+    pub emphasis: bool,
+}
+
+// 親文字のマスの範囲とルビ
+pub struct RubyMark {
+    pub first_cell: usize,
+    pub cells: usize,
+    pub text: String,
+}
+
+// 縦書きの一列（右から順に並ぶ）
+pub struct Column {
+    pub char_index: usize,
+    pub cells: Vec<Cell>,
+    pub rubies: Vec<RubyMark>,
+}
+
+// 縦中横にする数字の桁数
+const HORIZONTAL_DIGITS: usize = 2;
+
+const ROTATED: &str = "ー−－—―…‥～〜「」『』（）【】〔〕［］｛｝〈〉《》→←＝";
+
+pub fn glyph_kind(c: char) -> GlyphKind {
+    match c {
+        '、' | '。' | '，' | '．' => GlyphKind::Punctuation,
+        'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'っ' | 'ゃ' | 'ゅ' | 'ょ' | 'ゎ' | 'ァ' | 'ィ'
+        | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' | 'ヵ' | 'ヶ' => {
+            GlyphKind::Small
+        }
+        c if c.is_ascii_graphic() || ROTATED.contains(c) => GlyphKind::Rotated,
+        _ => GlyphKind::Upright,
+    }
+}
+
+// 一列 rows マスで文書全体を縦書きに組む
+pub fn layout(text: &str, rows: usize) -> Vec<Column> {
+    let rows = rows.max(2);
+    let mut columns = Vec::new();
+    let mut char_index = 0;
+
+    for line in text.split('\n') {
+        let (cells, rubies) = line_cells(line, char_index);
+        char_index += line.chars().count() + 1;
+
+        let mut column = Column {
+            char_index: cells.first().map_or(char_index - 1, |cell| cell.char_index),
+            cells: Vec::new(),
+            rubies: Vec::new(),
+        };
+        let mut ruby_iter = rubies.into_iter().peekable();
+        let mut line_cells = cells.into_iter().peekable();
+        let mut first_in_line = 0;
+
+        while let Some(cell) = line_cells.next() {
+            let position = column.cells.len();
+            let first_char = cell.text.chars().next().unwrap_or(' ');
+            let next_char = line_cells.peek().and_then(|next| next.text.chars().next());
+
+            // 行頭禁則の文字は前の列の末尾にぶら下げ、行末禁則の文字は次の列へ送る
+            let hanging = position >= rows && is_line_start_prohibited(first_char);
+            let push_out =
+                position == rows - 1 && is_line_end_prohibited(first_char) && next_char.is_some();
+            if (position >= rows && !hanging) || push_out {
+                first_in_line += column.cells.len();
+                let char_index = cell.char_index;
+                columns.push(std::mem::replace(
+                    &mut column,
+                    Column {
+                        char_index,
+                        cells: Vec::new(),
+                        rubies: Vec::new(),
+                    },
+                ));
+            }
+
+            // ルビは親文字の始まる列に付ける
+            let cell_number = first_in_line + column.cells.len();
+            while let Some(ruby) = ruby_iter.next_if(|ruby| ruby.first_cell <= cell_number) {
+                column.rubies.push(RubyMark {
+                    first_cell: ruby.first_cell - first_in_line,
+                    ..ruby
+                });
+            }
+            column.cells.push(cell);
+        }
+        columns.push(column);
+    }
+    columns
+}
+
+// 記法を除いた一行分のマス（ルビのマス位置は行頭から数える）
+fn line_cells(line: &str, line_char_index: usize) -> (Vec<Cell>, Vec<RubyMark>) {
+    let rubies = find_rubies(line);
+    let marks = Marks {
+        emphasis: find_emphasis(line),
+    };
+    let mut cells = Vec::new();
+    let mut ruby_marks = Vec::new();
+
+    let mut ruby_iter = rubies.iter().peekable();
+    let mut char_index = line_char_index;
+    let mut byte = 0;
+    while byte < line.len() {
+        if let Some(ruby) = ruby_iter.next_if(|ruby| ruby.range.start == byte) {
+            // 区切りの "｜" とルビの部分はマスに入れない
+            char_index += line[ruby.range.start..ruby.base.start].chars().count();
+            let first_cell = cells.len();
+            push_cells(line, ruby.base.clone(), &marks, &mut char_index, &mut cells);
+            ruby_marks.push(RubyMark {
+                first_cell,
+                cells: cells.len() - first_cell,
+                text: line[ruby.ruby.clone()].to_string(),
+            });
+            char_index += line[ruby.base.end..ruby.range.end].chars().count();
+            byte = ruby.range.end;
+        } else {
+            let end = ruby_iter.peek().map_or(line.len(), |ruby| ruby.range.start);
+            push_cells(line, byte..end, &marks, &mut char_index, &mut cells);
+            byte = end;
+        }
+    }
+    (cells, ruby_marks)
+}
+
+// 傍点の範囲（記法の "《《" "》》" はマスに入れない）
+struct Marks {
+    emphasis: Vec<Emphasis>,
+}
+
+impl Marks {
+    fn is_markup(&self, byte: usize) -> bool {
+        self.emphasis
+            .iter()
+            .any(|emphasis| emphasis.range.contains(&byte) && !emphasis.text.contains(&byte))
+    }
+
+    fn is_emphasis(&self, byte: usize) -> bool {
+        self.emphasis
+            .iter()
+            .any(|emphasis| emphasis.text.contains(&byte))
+    }
+}
+
+fn push_cells(
+    line: &str,
+    range: Range<usize>,
+    marks: &Marks,
+    char_index: &mut usize,
+    cells: &mut Vec<Cell>,
+) {
+    let chars: Vec<(usize, char)> = line[range.clone()]
+        .char_indices()
+        .map(|(i, c)| (range.start + i, c))
+        .collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let (byte, c) = chars[i];
+        if marks.is_markup(byte) {
+            *char_index += 1;
+            i += 1;
+            continue;
+        }
+
+        // 短い数字の並びは縦中横（長い並びは一文字ずつ回転する）
+        let digits = chars[i..]
+            .iter()
+            .take_while(|(_, c)| c.is_ascii_digit())
+            .count();
+        let after_digit = i > 0 && chars[i - 1].1.is_ascii_digit();
+        let (text, kind, len) = if (1..=HORIZONTAL_DIGITS).contains(&digits) && !after_digit {
+            let text = chars[i..i + digits].iter().map(|(_, c)| c).collect();
+            (text, GlyphKind::Horizontal, digits)
+        } else {
+            (c.to_string(), glyph_kind(c), 1)
+        };
+        cells.push(Cell {
+            text,
+            kind,
+            char_index: *char_index,
+            emphasis: marks.is_emphasis(byte),
+        });
+        *char_index += len;
+        i += len;
+    }
+}
@@ -0,0 +1,49 @@
+use crate::text::indent::INDENT;
+use std::ops::Range;
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | INDENT)
+}
+
+// 行末の空白（範囲は行内のバイト位置）
+pub fn trailing_whitespace(line: &str) -> Option<Range<usize>> {
+    let trimmed = line.trim_end_matches(is_space).len();
+    (trimmed < line.len()).then_some(trimmed..line.len())
+}
+
+// 行頭の空白に半角スペース・タブ・全角スペースが混ざっていればその範囲
+pub fn mixed_indentation(line: &str) -> Option<Range<usize>> {
+    let leading = line.len() - line.trim_start_matches(is_space).len();
+    let indent = &line[..leading];
+    let kinds = [' ', '\t', INDENT]
+        .iter()
+        .filter(|&&kind| indent.contains(kind))
+        .count();
+    (kinds > 1).then_some(0..leading)
+}
+
+// 行末の空白を取り除き、混在した行頭の空白を全角スペースにそろえる
+// （全角スペースとタブは一つずつ、半角スペースは二つで全角スペース一つに数える）
+pub fn clean_whitespace(text: &str) -> String {
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            let line = match trailing_whitespace(line) {
+                Some(range) => &line[..range.start],
+                None => line,
+            };
+            let Some(indent) = mixed_indentation(line) else {
+                return line.to_string();
+            };
+            let spaces = line[indent.clone()].chars().filter(|&c| c == ' ').count();
+            let others = line[indent.clone()].chars().filter(|&c| c != ' ').count();
+            let width = others + spaces.div_ceil(2);
+            format!(
+                "{}{}",
+                INDENT.to_string().repeat(width),
+                &line[indent.end..]
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
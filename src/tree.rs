@@ -0,0 +1,376 @@
+use crate::ignore::IgnoreRules;
+use crate::workspace::relative_key;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 手動の並び順を保存するファイル（各フォルダに置く）
+const ORDER_FILE: &str = ".txt-editor-order";
+
+// 大きなフォルダで一度に表示する項目数
+pub const PAGE_SIZE: usize = 500;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortMode {
+    Name,
+    Modified,
+    Size,
+    Manual,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 4] = [
+        SortMode::Name,
+        SortMode::Modified,
+        SortMode::Size,
+        SortMode::Manual,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Name => "Name",
+            SortMode::Modified => "Modified",
+            SortMode::Size => "Size",
+            SortMode::Manual => "Manual",
+        }
+    }
+}
+
+pub struct TreeOptions {
+    pub sort_mode: SortMode,
+    pub folders_first: bool,
+    pub show_hidden: bool,
+    pub filter: String,
+    pub hidden_extensions: BTreeSet<String>,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        Self {
+            sort_mode: SortMode::Name,
+            folders_first: true,
+            show_hidden: false,
+            filter: String::new(),
+            hidden_extensions: BTreeSet::new(),
+        }
+    }
+}
+
+// 隠しファイル・無視パターン・拡張子の設定で表示する項目か
+pub fn is_visible(path: &Path, root: &Path, options: &TreeOptions, ignore: &IgnoreRules) -> bool {
+    let name = file_name(path);
+    if name.starts_with('.') && !options.show_hidden {
+        return false;
+    }
+
+    let is_dir = path.is_dir();
+    if let Some(relative) = relative_key(root, path) {
+        if ignore.is_ignored(&relative, is_dir) {
+            return false;
+        }
+    }
+
+    is_dir
+        || !path
+            .extension()
+            .is_some_and(|ext| options.hidden_extensions.contains(&extension_key(ext)))
+}
+
+pub fn extension_key(extension: &std::ffi::OsStr) -> String {
+    extension.to_string_lossy().to_lowercase()
+}
+
+// 絞り込みの文字列に一致するか（フォルダは配下に一致する項目があれば表示）
+pub fn matches_filter(
+    path: &Path,
+    root: &Path,
+    options: &TreeOptions,
+    ignore: &IgnoreRules,
+) -> bool {
+    const MAX_DEPTH: usize = 16;
+
+    fn matches(
+        path: &Path,
+        root: &Path,
+        options: &TreeOptions,
+        ignore: &IgnoreRules,
+        filter: &str,
+        depth: usize,
+    ) -> bool {
+        if file_name(path).to_lowercase().contains(filter) {
+            return true;
+        }
+        if depth >= MAX_DEPTH || !path.is_dir() || path.is_symlink() {
+            return false;
+        }
+        fs::read_dir(path)
+            .map(|entries| {
+                entries.flatten().any(|entry| {
+                    let child = entry.path();
+                    is_visible(&child, root, options, ignore)
+                        && matches(&child, root, options, ignore, filter, depth + 1)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    let filter = options.filter.trim().to_lowercase();
+    filter.is_empty() || matches(path, root, options, ignore, &filter, 0)
+}
+
+// ワークスペース内にある拡張子の一覧（拡張子フィルタの候補）
+pub fn collect_extensions(
+    root: &Path,
+    options: &TreeOptions,
+    ignore: &IgnoreRules,
+) -> BTreeSet<String> {
+    const MAX_ENTRIES: usize = 10_000;
+
+    let mut extensions = BTreeSet::new();
+    let mut pending = vec![root.to_path_buf()];
+    let mut visited = 0;
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            visited += 1;
+            if visited > MAX_ENTRIES {
+                return extensions;
+            }
+            let path = entry.path();
+            let name = file_name(&path);
+            if (name.starts_with('.') && !options.show_hidden)
+                || relative_key(root, &path)
+                    .is_some_and(|relative| ignore.is_ignored(&relative, path.is_dir()))
+            {
+                continue;
+            }
+            if path.is_dir() {
+                if !path.is_symlink() {
+                    pending.push(path);
+                }
+            } else if let Some(extension) = path.extension() {
+                extensions.insert(extension_key(extension));
+            }
+        }
+    }
+    extensions
+}
+
+// フォルダ内の項目を並び替えて返す
+pub fn list_children(dir: &Path, options: &TreeOptions) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    sort_entries(dir, &mut entries, options);
+    entries
+}
+
+pub fn sort_entries(dir: &Path, entries: &mut [PathBuf], options: &TreeOptions) {
+    let by_name = |a: &PathBuf, b: &PathBuf| natural_cmp(&file_name(a), &file_name(b));
+
+    // 日時・サイズは項目ごとに一度だけ調べる（同じ値なら名前順を保つ）
+    match options.sort_mode {
+        SortMode::Name => entries.sort_by(by_name),
+        SortMode::Modified => {
+            entries.sort_by(by_name);
+            entries.sort_by_cached_key(|path| {
+                Reverse(fs::metadata(path).and_then(|m| m.modified()).ok())
+            });
+        }
+        SortMode::Size => {
+            entries.sort_by(by_name);
+            entries.sort_by_cached_key(|path| Reverse(fs::metadata(path).map_or(0, |m| m.len())));
+        }
+        SortMode::Manual => {
+            // 順序ファイルにない項目は名前順で後ろに並べる
+            let order = read_manual_order(dir);
+            entries.sort_by(by_name);
+            entries.sort_by_cached_key(|path| {
+                let name = file_name(path);
+                order.iter().position(|n| *n == name).unwrap_or(usize::MAX)
+            });
+            return;
+        }
+    }
+
+    if options.folders_first {
+        entries.sort_by_cached_key(|path| !path.is_dir());
+    }
+}
+
+// シンボリックリンクならリンク先を返す
+pub fn symlink_target(path: &Path) -> Option<PathBuf> {
+    if path.is_symlink() {
+        fs::read_link(path).ok()
+    } else {
+        None
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// 数字部分を数値として比較する（"2" が "10" より前）
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if digit_value(x).is_some() && digit_value(y).is_some() => {
+                let a_digits = take_digits(&mut a_chars);
+                let b_digits = take_digits(&mut b_chars);
+                let a_trimmed = a_digits.trim_start_matches('0');
+                let b_trimmed = b_digits.trim_start_matches('0');
+                let ordering = a_trimmed
+                    .len()
+                    .cmp(&b_trimmed.len())
+                    .then_with(|| a_trimmed.cmp(b_trimmed))
+                    .then_with(|| a_digits.len().cmp(&b_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+// 半角・全角の数字に対応
+fn digit_value(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => c.to_digit(10),
+        '０'..='９' => Some(c as u32 - '０' as u32),
+        _ => None,
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(value) = chars.peek().copied().and_then(digit_value) {
+        digits.push(char::from_digit(value, 10).unwrap());
+        chars.next();
+    }
+    digits
+}
+
+pub fn read_manual_order(dir: &Path) -> Vec<String> {
+    fs::read_to_string(dir.join(ORDER_FILE))
+        .map(|contents| {
+            contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn write_manual_order(dir: &Path, names: &[String]) -> io::Result<()> {
+    let mut contents = names.join("\n");
+    contents.push('\n');
+    fs::write(dir.join(ORDER_FILE), contents)
+}
+
+// 名前の変更・移動を順序ファイルに反映する
+// 同じフォルダ内なら同じ位置に新しい名前を入れ、別のフォルダへ移したら元の順序から除く
+// 入れ替えにも対応するよう、フォルダごとにまとめて置き換える
+pub fn rename_in_manual_order(moved: &[(PathBuf, PathBuf)]) -> io::Result<()> {
+    let dirs: BTreeSet<&Path> = moved.iter().filter_map(|(from, _)| from.parent()).collect();
+    for dir in dirs {
+        let mut names = read_manual_order(dir);
+        let mut changed = false;
+        for name in &mut names {
+            let Some((_, to)) = moved
+                .iter()
+                .find(|(from, _)| from.parent() == Some(dir) && file_name(from) == *name)
+            else {
+                continue;
+            };
+            *name = if to.parent() == Some(dir) {
+                file_name(to)
+            } else {
+                String::new()
+            };
+            changed = true;
+        }
+        if changed {
+            names.retain(|name| !name.is_empty());
+            write_manual_order(dir, &names)?;
+        }
+    }
+    Ok(())
+}
+
+// 手動の並び順で項目を上下に移動する
+pub fn move_in_manual_order(path: &Path, offset: isize, options: &TreeOptions) -> io::Result<()> {
+    let Some(dir) = path.parent() else {
+        return Ok(());
+    };
+    let mut names: Vec<String> = list_children(dir, options)
+        .iter()
+        .map(|child| file_name(child))
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+
+    let name = file_name(path);
+    let Some(index) = names.iter().position(|n| *n == name) else {
+        return Ok(());
+    };
+    let target = index as isize + offset;
+    if target < 0 || target as usize >= names.len() {
+        return Ok(());
+    }
+    names.swap(index, target as usize);
+    write_manual_order(dir, &names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_numbers_by_value() {
+        let mut names = vec!["第10話", "第2話", "第１話", "第02話", "あとがき"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["あとがき", "第１話", "第2話", "第02話", "第10話"]);
+
+        assert_eq!(natural_cmp("a2", "a10"), Ordering::Less);
+        assert_eq!(natural_cmp("File", "file"), Ordering::Equal);
+        assert_eq!(natural_cmp("chapter", "chapter1"), Ordering::Less);
+    }
+
+    #[test]
+    fn keeps_manual_order_across_renames() {
+        let dir = std::env::temp_dir().join(format!("txt-editor-order-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        let names = ["c", "a", "b"].map(String::from);
+        write_manual_order(&dir, &names).unwrap();
+
+        // a と b の入れ替えと、c の別のフォルダへの移動
+        let moved = [
+            (dir.join("a"), dir.join("b")),
+            (dir.join("b"), dir.join("a")),
+            (dir.join("c"), dir.join("sub").join("c")),
+        ];
+        rename_in_manual_order(&moved).unwrap();
+        assert_eq!(read_manual_order(&dir), ["b", "a"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+use crate::app::TxtEditorApp;
+use crate::document::{self, LineEnding, TextEncoding};
+use crate::file_operations::unique_destination;
+use crate::text::aozora::{export, import, ExportOptions};
+use eframe::egui::{self, Context, Grid};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 青空文庫形式で書き出すファイルと表題・著者名
+pub struct AozoraExport {
+    pub source: PathBuf,
+    pub title: String,
+    pub author: String,
+    pub shift_jis: bool,
+    pub error: Option<String>,
+}
+
+impl AozoraExport {
+    // 表題は先頭の見出しがあればそれを、なければファイル名を使う
+    pub fn new(source: &Path) -> Self {
+        let heading = document::load(source).ok().and_then(|loaded| {
+            let first = loaded.text.lines().next()?.to_string();
+            first
+                .strip_prefix("# ")
+                .map(|title| title.trim().to_string())
+        });
+        let title = heading.unwrap_or_else(|| {
+            source
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        Self {
+            source: source.to_path_buf(),
+            title,
+            author: String::new(),
+            shift_jis: true,
+            error: None,
+        }
+    }
+}
+
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(dialog) = &mut app.aozora_export else {
+        return;
+    };
+    let mut open = true;
+    let mut exported = false;
+
+    egui::Window::new("Export as Aozora Text")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            Grid::new("aozora_export").num_columns(2).show(ui, |ui| {
+                ui.label("Title");
+                ui.text_edit_singleline(&mut dialog.title);
+                ui.end_row();
+                ui.label("Author");
+                ui.text_edit_singleline(&mut dialog.author);
+                ui.end_row();
+            });
+            ui.checkbox(&mut dialog.shift_jis, "Shift_JIS with CRLF line endings");
+            if let Some(error) = &dialog.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            if ui.button("Export...").clicked() {
+                match export_file(dialog) {
+                    Ok(true) => exported = true,
+                    Ok(false) => {}
+                    Err(err) => dialog.error = Some(err),
+                }
+            }
+        });
+
+    if !open || exported {
+        app.aozora_export = None;
+        app.refresh_file_list();
+    }
+}
+
+// 保存先を選んで書き出す（キャンセルしたときは false）
+fn export_file(dialog: &AozoraExport) -> Result<bool, String> {
+    let loaded = document::load(&dialog.source).map_err(|err| err.to_string())?;
+    let Some(destination) = rfd::FileDialog::new()
+        .add_filter("Text", &["txt"])
+        .set_file_name(&format!("{}.txt", dialog.title))
+        .save_file()
+    else {
+        return Ok(false);
+    };
+
+    // 先頭の見出しは表題として冒頭に書くので本文からは除く
+    let heading = format!("# {}", dialog.title);
+    let body = match loaded.text.split_once('\n') {
+        Some((first, rest)) if first.trim() == heading => rest.trim_start_matches('\n'),
+        _ => loaded.text.as_str(),
+    };
+    let options = ExportOptions {
+        title: dialog.title.clone(),
+        author: dialog.author.clone(),
+    };
+    let (encoding, line_ending) = if dialog.shift_jis {
+        (TextEncoding::ShiftJis, LineEnding::CrLf)
+    } else {
+        (TextEncoding::Utf8, LineEnding::Lf)
+    };
+    document::save(&destination, &export(body, &options), encoding, line_ending)?;
+    Ok(true)
+}
+
+// 青空文庫形式のファイルを変換して dir に UTF-8 で保存し、開く
+// 文字コードは自動判定する（青空文庫のファイルは多くが Shift_JIS）
+pub fn import_file(app: &mut TxtEditorApp, source: &Path, dir: &Path) {
+    let Some(stem) = source.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+        return;
+    };
+    let destination = unique_destination(dir, &format!("{}.txt", stem));
+    let result =
+        document::load(source).and_then(|loaded| fs::write(&destination, import(&loaded.text)));
+    match result {
+        Ok(()) => {
+            app.refresh_file_list();
+            app.open_file(&destination);
+        }
+        Err(err) => eprintln!("Failed to import Aozora text: {}", err),
+    }
+}
@@ -0,0 +1,140 @@
+use crate::app::TxtEditorApp;
+use crate::file_types::EditorMode;
+use crate::text::indent::{closing_bracket, is_closing_bracket, INDENT};
+use crate::text::stats::TrackedText;
+use eframe::egui::{Context, Event, Id, Key, TextBuffer, TextEdit};
+
+// 入力を処理する前のカーソルの位置と、このフレームで入力された文字
+pub struct PendingInput {
+    cursor: usize,
+    next: Option<char>,
+    typed: Option<char>,
+    enter: bool,
+}
+
+// TextEdit が入力を処理する前に呼ぶ（補助の対象になる入力がなければ None）
+pub fn before_input(ctx: &Context, app: &TxtEditorApp, editor_id: Id) -> Option<PendingInput> {
+    if !matches!(
+        app.editor_mode,
+        EditorMode::PlainText | EditorMode::Markdown
+    ) {
+        return None;
+    }
+    if !ctx.memory(|memory| memory.has_focus(editor_id)) {
+        return None;
+    }
+    let range = TextEdit::load_state(ctx, editor_id)?.cursor.char_range()?;
+    // 選択範囲があるときは通常の入力に任せる
+    if range.primary.index != range.secondary.index {
+        return None;
+    }
+
+    let mut typed = None;
+    let mut enter = false;
+    ctx.input(|input| {
+        for event in &input.events {
+            match event {
+                // IME で確定した文字は CompositionEnd で届く
+                Event::Text(text) | Event::CompositionEnd(text) => {
+                    let mut chars = text.chars();
+                    if let (Some(c), None) = (chars.next(), chars.next()) {
+                        typed = Some(c);
+                    }
+                }
+                Event::Key {
+                    key: Key::Enter,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if modifiers.is_none() => enter = true,
+                _ => {}
+            }
+        }
+    });
+    if typed.is_none() && !enter {
+        return None;
+    }
+
+    let cursor = range.primary.index;
+    let text = &app.file_contents;
+    let next = text[text.byte_index_from_char_index(cursor)..]
+        .chars()
+        .next();
+    Some(PendingInput {
+        cursor,
+        next,
+        typed,
+        enter,
+    })
+}
+
+// TextEdit が入力を処理した後に呼び、字下げと括弧を補う
+// 文書を変更したときは新しいカーソルの位置を返す
+pub fn after_input(app: &mut TxtEditorApp, input: PendingInput, cursor: usize) -> Option<usize> {
+    let rules = &app.indent_rules;
+    // 見出しやリストの続きを字下げしないよう、Markdown では字下げを補わない
+    let auto_indent = rules.auto_indent && app.editor_mode == EditorMode::PlainText;
+    let mut buffer = TrackedText {
+        text: &mut app.file_contents,
+        stats: &mut app.stats,
+    };
+    let char_at = |buffer: &TrackedText, index: usize| {
+        let text = buffer.as_str();
+        text[text.byte_index_from_char_index(index)..]
+            .chars()
+            .next()
+    };
+    let line_start = |buffer: &TrackedText, index: usize| {
+        let text = buffer.as_str();
+        let byte = text.byte_index_from_char_index(index);
+        let start = text[..byte].rfind('\n').map_or(0, |i| i + 1);
+        text[..start].chars().count()
+    };
+
+    if input.enter && cursor == input.cursor + 1 && auto_indent {
+        let mut cursor = cursor;
+        // 字下げだけの行で改行したら、その行は空行にする
+        let previous_start = line_start(&buffer, cursor - 1);
+        if buffer.char_range(previous_start..cursor - 1) == INDENT.to_string() {
+            buffer.delete_char_range(previous_start..previous_start + 1);
+            cursor -= 1;
+        }
+        let previous = buffer.char_range(previous_start..cursor - 1).to_string();
+        let rest = char_at(&buffer, cursor);
+        let indent = !previous.starts_with('#')
+            && !rest.is_some_and(|c| c == ' ' || c == INDENT || rules.is_exempt(c));
+        if indent {
+            buffer.insert_text(&INDENT.to_string(), cursor);
+            cursor += 1;
+        }
+        return Some(cursor);
+    }
+
+    let typed = input.typed?;
+    if cursor != input.cursor + 1 || char_at(&buffer, cursor - 1) != Some(typed) {
+        return None;
+    }
+
+    // 直後にある閉じ括弧は入力せずに通り過ぎる
+    if is_closing_bracket(typed) && input.next == Some(typed) && rules.auto_pair {
+        buffer.delete_char_range(cursor..cursor + 1);
+        return Some(cursor);
+    }
+
+    let close = closing_bracket(typed)?;
+    let mut cursor = cursor;
+    // 字下げしない文字で始まる段落は、自動で入れた字下げを取り除く
+    let start = line_start(&buffer, cursor);
+    if auto_indent
+        && rules.is_exempt(typed)
+        && start + 2 == cursor
+        && char_at(&buffer, start) == Some(INDENT)
+    {
+        buffer.delete_char_range(start..start + 1);
+        cursor -= 1;
+    }
+    if rules.auto_pair {
+        buffer.insert_text(&close.to_string(), cursor);
+    }
+    (rules.auto_pair || cursor != input.cursor + 1).then_some(cursor)
+}
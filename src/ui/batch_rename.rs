@@ -0,0 +1,172 @@
+use crate::app::TxtEditorApp;
+use crate::batch_rename::{apply, CaseConversion, WidthConversion};
+use eframe::egui::{self, Color32, ComboBox, Context, DragValue, Grid, ScrollArea};
+
+// 一括リネームのダイアログ
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(dialog) = &mut app.batch_rename else {
+        return;
+    };
+    let mut close = false;
+    let mut renamed = None;
+
+    egui::Window::new("Batch Rename")
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            let options = &mut dialog.options;
+            ui.label(format!("{} item(s)", dialog.targets.len()));
+            ui.separator();
+
+            Grid::new("batch_rename_options")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Find:");
+                    ui.text_edit_singleline(&mut options.find);
+                    ui.end_row();
+
+                    ui.label("Replace:");
+                    ui.text_edit_singleline(&mut options.replace);
+                    ui.end_row();
+
+                    ui.label("");
+                    ui.checkbox(&mut options.use_regex, "Regular expression");
+                    ui.end_row();
+
+                    ui.label("Case:");
+                    ComboBox::from_id_source("batch_rename_case")
+                        .selected_text(match options.case {
+                            CaseConversion::None => "Keep",
+                            CaseConversion::Upper => "UPPER",
+                            CaseConversion::Lower => "lower",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut options.case, CaseConversion::None, "Keep");
+                            ui.selectable_value(&mut options.case, CaseConversion::Upper, "UPPER");
+                            ui.selectable_value(&mut options.case, CaseConversion::Lower, "lower");
+                        });
+                    ui.end_row();
+
+                    ui.label("Width:");
+                    ComboBox::from_id_source("batch_rename_width")
+                        .selected_text(match options.width {
+                            WidthConversion::None => "Keep",
+                            WidthConversion::ToFullWidth => "Full-width (ａ１)",
+                            WidthConversion::ToHalfWidth => "Half-width (a1)",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut options.width, WidthConversion::None, "Keep");
+                            ui.selectable_value(
+                                &mut options.width,
+                                WidthConversion::ToFullWidth,
+                                "Full-width (ａ１)",
+                            );
+                            ui.selectable_value(
+                                &mut options.width,
+                                WidthConversion::ToHalfWidth,
+                                "Half-width (a1)",
+                            );
+                        });
+                    ui.end_row();
+
+                    ui.label("");
+                    ui.checkbox(&mut options.numbering, "Sequential numbering");
+                    ui.end_row();
+
+                    if options.numbering {
+                        ui.label("Start / Step:");
+                        ui.horizontal(|ui| {
+                            ui.add(DragValue::new(&mut options.start));
+                            ui.add(DragValue::new(&mut options.step).clamp_range(1..=1000));
+                        });
+                        ui.end_row();
+
+                        ui.label("Padding:");
+                        ui.add(DragValue::new(&mut options.padding).clamp_range(1..=8));
+                        ui.end_row();
+
+                        ui.label("Separator:");
+                        ui.text_edit_singleline(&mut options.separator);
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(
+                            &mut options.strip_existing_number,
+                            "Replace existing leading number",
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            let previews = dialog.previews();
+            match &previews {
+                Ok(previews) => {
+                    ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        Grid::new("batch_rename_preview")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for preview in previews {
+                                    ui.label(preview.from.file_name().unwrap().to_string_lossy());
+                                    ui.label("→");
+                                    let new_name = preview
+                                        .to
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy();
+                                    match &preview.conflict {
+                                        Some(conflict) => {
+                                            ui.colored_label(Color32::RED, new_name)
+                                                .on_hover_text(conflict);
+                                        }
+                                        None if preview.is_changed() => {
+                                            ui.strong(new_name);
+                                        }
+                                        None => {
+                                            ui.weak(new_name);
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                }
+                Err(err) => {
+                    ui.colored_label(Color32::RED, format!("Invalid pattern: {}", err));
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let can_apply = previews.as_ref().is_ok_and(|previews| {
+                    previews.iter().all(|p| p.conflict.is_none())
+                        && previews.iter().any(|p| p.is_changed())
+                });
+                if ui
+                    .add_enabled(can_apply, egui::Button::new("Rename"))
+                    .clicked()
+                {
+                    match apply(previews.as_ref().unwrap()) {
+                        Ok(result) => renamed = Some(result),
+                        // 失敗する前に変更できた分はタグなどに反映する
+                        Err((result, err)) => {
+                            eprintln!("Failed to rename items: {}", err);
+                            renamed = Some(result);
+                        }
+                    }
+                    close = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if let Some(renamed) = renamed {
+        app.paths_moved(&renamed);
+    }
+    if close {
+        app.batch_rename = None;
+        app.refresh_file_list();
+    }
+}
@@ -0,0 +1,169 @@
+use crate::app::TxtEditorApp;
+use crate::document::{LineEnding, TextEncoding};
+use crate::file_types::EditorMode;
+use eframe::egui::{self, Color32, Context, Key, Modifiers, TopBottomPanel};
+
+// ステータスバー（各項目をクリックすると関連する操作を開く）
+pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
+    if ctx.input(|i| i.key_pressed(Key::G) && i.modifiers == Modifiers::CTRL) {
+        open_goto_line(app);
+    }
+    if app.goto_line_popup {
+        goto_line_popup(ctx, app);
+    }
+    if app.stats_window {
+        crate::ui::statistics::display(ctx, app);
+    }
+
+    TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            if app.selected_file.is_none() {
+                ui.label("No file open");
+                return;
+            }
+
+            let mut position = format!("Ln {}, Col {}", app.cursor_line, app.cursor_column);
+            if !app.selection_range.is_empty() {
+                position.push_str(&format!(" ({} selected)", app.selection_range.len()));
+            }
+            if ui.button(position).on_hover_text("Go to Line").clicked() {
+                open_goto_line(app);
+            }
+            ui.separator();
+
+            let total = app.stats.total().counted(app.exclude_markup);
+            let characters = format!(
+                "{} characters ({} pages)",
+                total.total_chars(),
+                total.manuscript_pages()
+            );
+            if ui.button(characters).on_hover_text("Statistics").clicked() {
+                app.stats_window = true;
+            }
+            let problems = format!("{} problems", app.diagnostics.len());
+            if ui
+                .selectable_label(app.show_problems, problems)
+                .on_hover_text("Problems")
+                .clicked()
+            {
+                app.show_problems = !app.show_problems;
+            }
+            ui.separator();
+
+            ui.menu_button(app.encoding.label(), |ui| encoding_menu(ui, app));
+            ui.menu_button(app.line_ending.label(), |ui| {
+                for line_ending in LineEnding::ALL {
+                    if ui
+                        .radio(app.line_ending == line_ending, line_ending.label())
+                        .clicked()
+                    {
+                        app.line_ending = line_ending;
+                        app.file_modified = true;
+                        ui.close_menu();
+                    }
+                }
+            });
+            ui.separator();
+
+            if ui
+                .button(format_size(app.file_size))
+                .on_hover_text("Reveal Active File")
+                .clicked()
+            {
+                app.reveal_active_file();
+            }
+            ui.separator();
+
+            let state = match &app.save_error {
+                Some(err) => ui.add(egui::Button::new(
+                    egui::RichText::new(format!("Save failed: {}", err)).color(Color32::RED),
+                )),
+                None if app.file_modified => ui.button("Modified"),
+                None => ui.button("Saved"),
+            };
+            if state.on_hover_text("Save Now").clicked() {
+                app.failed_save = None;
+                app.save_file_if_modified();
+            }
+            ui.separator();
+
+            ui.menu_button(app.editor_mode.label(), |ui| {
+                for mode in EditorMode::ALL {
+                    if ui.radio(app.editor_mode == mode, mode.label()).clicked() {
+                        app.editor_mode = mode;
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+    });
+}
+
+// 文字コードの読み直し・変更
+fn encoding_menu(ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    ui.menu_button("Reopen with Encoding", |ui| {
+        for encoding in TextEncoding::ALL {
+            if ui.button(encoding.label()).clicked() {
+                app.reopen_with_encoding(encoding);
+                ui.close_menu();
+            }
+        }
+    });
+    ui.menu_button("Save with Encoding", |ui| {
+        for encoding in TextEncoding::ALL {
+            if ui
+                .radio(app.encoding == encoding, encoding.label())
+                .clicked()
+            {
+                app.encoding = encoding;
+                app.file_modified = true;
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{} B", size),
+        1024..=1_048_575 => format!("{:.1} KB", size as f64 / 1024.0),
+        _ => format!("{:.1} MB", size as f64 / 1_048_576.0),
+    }
+}
+
+fn open_goto_line(app: &mut TxtEditorApp) {
+    if app.selected_file.is_some() {
+        app.goto_line_input = app.cursor_line.to_string();
+        app.goto_line_popup = true;
+    }
+}
+
+// 行番号を指定して移動するポップアップ
+fn goto_line_popup(ctx: &Context, app: &mut TxtEditorApp) {
+    let line_count = app.stats.line_count();
+    egui::Window::new("Go to Line").show(ctx, |ui| {
+        ui.label(format!("Line number (1 - {}):", line_count));
+        let response = ui.text_edit_singleline(&mut app.goto_line_input);
+        response.request_focus();
+
+        let line = app
+            .goto_line_input
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|line| (1..=line_count).contains(line));
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        ui.horizontal(|ui| {
+            let go = ui.add_enabled(line.is_some(), egui::Button::new("Go"));
+            if let Some(line) = line {
+                if go.clicked() || submitted {
+                    app.go_to_line(line);
+                    app.goto_line_popup = false;
+                }
+            }
+            if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(Key::Escape)) {
+                app.goto_line_popup = false;
+            }
+        });
+    });
+}
@@ -1,21 +1,388 @@
 use crate::app::TxtEditorApp;
-use eframe::egui::{self, CentralPanel, Context, ScrollArea, TextEdit};
+use crate::file_types::EditorMode;
+use crate::text::columns::parse_rulers;
+use crate::text::convert::TextConversion;
+use crate::text::indent::normalize_indentation;
+use crate::text::stats::{count, TrackedText};
+use crate::text::whitespace::clean_whitespace;
+use crate::text::{emphasis, ruby};
+use crate::ui::assist::{after_input, before_input};
+use crate::ui::convert::ConversionPreview;
+use crate::ui::highlight::{
+    align_to_columns, emphasis_spans, layout_job, lint_spans, ruby_spans, whitespace_spans,
+};
+use crate::ui::{gutter, ruler};
+use eframe::egui::text::{CCursor, CCursorRange};
+use eframe::egui::Galley;
+use eframe::egui::{
+    self, CentralPanel, Context, Id, Key, Modifiers, ScrollArea, TextBuffer, TextEdit,
+};
+use std::sync::Arc;
+
+// 中央のエディタの ID（取り消しの履歴を外から操作するときに使う）
+pub const EDITOR_ID: &str = "central_editor";
+// エディタのレイアウトの使い回し（記法の色分けと桁揃えは文書全体をたどるので）
+pub struct EditorLayout {
+    key: LayoutKey,
+    galley: Arc<Galley>,
+}
+
+#[derive(PartialEq)]
+struct LayoutKey {
+    // 校正結果の版、空白の強調、折り返しの桁数、桁揃え、文字の大きさ、ダークモード
+    settings: (u64, bool, usize, bool, u32, bool),
+    wrap_width: u32,
+}
+
+// 桁で折り返すように切り替えたときの桁数
+const DEFAULT_WRAP_COLUMNS: usize = 40;
 
 pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
     CentralPanel::default().show(ctx, |ui| {
         if let Some(_) = app.selected_file {
             ScrollArea::vertical().show(ui, |ui| {
-                let response = ui.add(
-                    TextEdit::multiline(&mut app.file_contents)
-                        .font(egui::TextStyle::Monospace)
-                        .desired_rows(30)
-                        .desired_width(f32::INFINITY),
+                // 行移動などで指定されたカーソル位置を反映する
+                let editor_id = Id::new(EDITOR_ID);
+                let pending_selection = app.pending_selection.take();
+                if let Some(range) = &pending_selection {
+                    let mut state = TextEdit::load_state(ctx, editor_id).unwrap_or_default();
+                    state.cursor.set_char_range(Some(CCursorRange::two(
+                        CCursor::new(range.start),
+                        CCursor::new(range.end),
+                    )));
+                    TextEdit::store_state(ctx, editor_id, state);
+                }
+
+                // 字下げと括弧の補助（TextEdit が入力を処理する前の状態を覚えておく）
+                let pending_input = before_input(ctx, app, editor_id);
+                let gutter_width = gutter::width(ui, app);
+
+                // ルビなどの記法を色分けする
+                let font = egui::TextStyle::Monospace.resolve(ui.style());
+                // 桁で折り返すときとルーラーを引くときは、半角を全角の半分の幅に揃える
+                let full_width = ruler::full_width(ui, &font);
+                let align_columns = app.wrap_columns > 0 || !app.rulers.is_empty();
+                let settings = (
+                    app.diagnostics_revision,
+                    app.highlight_whitespace,
+                    app.wrap_columns,
+                    align_columns,
+                    font.size.to_bits(),
+                    ui.visuals().dark_mode,
+                );
+                let cache = &mut app.editor_layout;
+                let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                    // 文書と設定が変わらなければ前回の結果を使う
+                    // TextEdit は編集した直後にも同じフレームで呼ぶので、text_revision ではなく内容を比べる
+                    let key = LayoutKey {
+                        settings,
+                        wrap_width: wrap_width.to_bits(),
+                    };
+                    if let Some(cached) = cache
+                        .as_ref()
+                        .filter(|cached| cached.key == key && cached.galley.job.text == text)
+                    {
+                        return cached.galley.clone();
+                    }
+
+                    let mut spans = Vec::new();
+                    ruby_spans(text, &mut spans);
+                    emphasis_spans(text, &mut spans);
+                    if app.highlight_whitespace {
+                        whitespace_spans(text, &mut spans);
+                    }
+                    lint_spans(&app.diagnostics, &mut spans);
+                    let mut job = layout_job(ui, text, font.clone(), &spans);
+                    if align_columns {
+                        align_to_columns(ui, &mut job, full_width);
+                    }
+                    job.wrap.max_width = match app.wrap_columns {
+                        0 => wrap_width,
+                        columns => wrap_width.min(columns as f32 * full_width + 0.5),
+                    };
+                    let galley = ui.fonts(|fonts| fonts.layout_job(job));
+                    *cache = Some(EditorLayout {
+                        key,
+                        galley: galley.clone(),
+                    });
+                    galley
+                };
+
+                // 行番号の欄の分だけ右にずらして表示する
+                let mut buffer = TrackedText {
+                    text: &mut app.file_contents,
+                    stats: &mut app.stats,
+                };
+                let output = ui
+                    .horizontal_top(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.0;
+                        ui.add_space(gutter_width);
+                        TextEdit::multiline(&mut buffer)
+                            .id(editor_id)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_rows(30)
+                            .desired_width(f32::INFINITY)
+                            .layouter(&mut layouter)
+                            .show(ui)
+                    })
+                    .inner;
+                let response = &output.response;
+                if let Some(lines) = gutter::show(ui, app, &output, gutter_width) {
+                    app.pending_selection = Some(lines);
+                    app.focus_editor = true;
+                }
+                if app.show_whitespace {
+                    crate::ui::whitespace::paint(ui, &output.galley, output.galley_pos);
+                }
+                ruler::paint(
+                    ui,
+                    &app.rulers,
+                    output.galley_pos,
+                    response.rect,
+                    full_width,
                 );
 
+                if let Some(range) = pending_selection {
+                    let cursor = output.galley.from_ccursor(CCursor::new(range.end));
+                    let cursor_rect = output.galley.pos_from_cursor(&cursor);
+                    ui.scroll_to_rect(cursor_rect.translate(output.galley_pos.to_vec2()), None);
+                }
+
+                // 指摘の上にマウスを置くと内容を表示する
+                if let Some(pointer) = response.hover_pos() {
+                    let cursor = output.galley.cursor_from_pos(pointer - output.galley_pos);
+                    let index = cursor.ccursor.index;
+                    let messages: Vec<&str> = app
+                        .diagnostics
+                        .iter()
+                        .filter(|diagnostic| diagnostic.chars.contains(&index))
+                        .map(|diagnostic| diagnostic.message.as_str())
+                        .collect();
+                    if !messages.is_empty() {
+                        response
+                            .clone()
+                            .on_hover_text_at_pointer(messages.join("\n"));
+                    }
+                }
+
+                if app.focus_editor {
+                    response.request_focus();
+                    app.focus_editor = false;
+                }
+                if response.has_focus() {
+                    app.tree_has_focus = false;
+                    if ctx.input(|i| i.key_pressed(Key::R) && i.modifiers == Modifiers::CTRL) {
+                        open_ruby_popup(app);
+                    }
+                    if ctx.input(|i| i.key_pressed(Key::E) && i.modifiers == Modifiers::CTRL) {
+                        add_emphasis(ctx, app);
+                    }
+                }
+                response.context_menu(|ui| {
+                    if ui.button("Insert Ruby...").clicked() {
+                        open_ruby_popup(app);
+                        ui.close_menu();
+                    }
+                    let has_selection = !app.selection_range.is_empty();
+                    if ui
+                        .add_enabled(has_selection, egui::Button::new("Add Emphasis Dots"))
+                        .clicked()
+                    {
+                        add_emphasis(ctx, app);
+                        ui.close_menu();
+                    }
+                    ui.menu_button("Editing Assist", |ui| editing_assist_menu(ctx, ui, app));
+                    ui.menu_button("Whitespace", |ui| whitespace_menu(ctx, ui, app));
+                    ui.menu_button("Line Numbers", |ui| {
+                        ui.checkbox(&mut app.show_line_numbers, "Show Line Numbers");
+                        ui.checkbox(&mut app.visual_line_numbers, "Number Wrapped Lines");
+                        ui.checkbox(&mut app.highlight_current_line, "Highlight Current Line");
+                    });
+                    ui.menu_button("Wrap", |ui| wrap_menu(ui, app));
+                    ui.menu_button("Convert", |ui| {
+                        for conversion in TextConversion::ALL {
+                            if ui.button(conversion.label()).clicked() {
+                                app.conversion_preview =
+                                    Some(ConversionPreview::new(app, conversion));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+
                 if response.changed() {
                     app.file_modified = true;
+                    app.text_revision += 1;
+
+                    let cursor = output.cursor_range.map(|range| range.primary.ccursor.index);
+                    if let Some((input, cursor)) = pending_input.zip(cursor) {
+                        if let Some(cursor) = after_input(app, input, cursor) {
+                            let mut state = output.state.clone();
+                            state
+                                .cursor
+                                .set_char_range(Some(CCursorRange::one(CCursor::new(cursor))));
+                            state.store(ctx, editor_id);
+                            ctx.request_repaint();
+                        }
+                    }
+                }
+
+                if let Some(range) = output.cursor_range {
+                    let primary = range.primary;
+                    app.cursor_line = primary.pcursor.paragraph + 1;
+                    app.cursor_column = primary.pcursor.offset + 1;
+                    // 選択範囲の集計は範囲か内容が変わったときだけ数え直す
+                    let [start, end] = range.sorted_cursors();
+                    let selection_range = start.ccursor.index..end.ccursor.index;
+                    if selection_range != app.selection_range || response.changed() {
+                        app.selection_counts =
+                            count(app.file_contents.char_range(selection_range.clone()));
+                        app.selection_range = selection_range;
+                    }
                 }
             });
         }
     });
+
+    if app.ruby_popup {
+        ruby_popup(ctx, app);
+    }
+    if app.conversion_preview.is_some() {
+        crate::ui::convert::display(ctx, app);
+    }
+}
+
+// 字下げ・括弧の補助の設定（ワークスペースごとに保存する）
+fn editing_assist_menu(ctx: &Context, ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    let rules = &mut app.indent_rules;
+    ui.checkbox(&mut rules.auto_indent, "Indent New Paragraphs")
+        .on_hover_text("Plain text files only");
+    ui.checkbox(&mut rules.auto_pair, "Auto-pair Brackets");
+    ui.horizontal(|ui| {
+        ui.label("No indent before:");
+        ui.add(egui::TextEdit::singleline(&mut rules.exempt).desired_width(120.0));
+    });
+    ui.separator();
+    // 字下げの規則は原稿用なので、プレーンテキスト以外（Markdown・JSON など）には使わない
+    let plain_text = app.editor_mode == EditorMode::PlainText;
+    if ui
+        .add_enabled(plain_text, egui::Button::new("Normalize Indentation"))
+        .on_disabled_hover_text("Plain text files only")
+        .clicked()
+    {
+        let normalized = normalize_indentation(&app.file_contents, &app.indent_rules);
+        if normalized != app.file_contents {
+            let end = app.file_contents.chars().count();
+            app.replace_range(ctx, 0..end, &normalized);
+        }
+        ui.close_menu();
+    }
+}
+
+fn whitespace_menu(ctx: &Context, ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    ui.checkbox(&mut app.show_whitespace, "Show Whitespace");
+    ui.checkbox(
+        &mut app.highlight_whitespace,
+        "Highlight Trailing and Mixed Whitespace",
+    );
+    ui.separator();
+    if ui.button("Strip Trailing and Mixed Whitespace").clicked() {
+        let cleaned = clean_whitespace(&app.file_contents);
+        if cleaned != app.file_contents {
+            let end = app.file_contents.chars().count();
+            app.replace_range(ctx, 0..end, &cleaned);
+        }
+        ui.close_menu();
+    }
+}
+
+// 折り返しの桁数とルーラー（ワークスペースごとに保存する）
+fn wrap_menu(ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    let mut fixed = app.wrap_columns > 0;
+    ui.radio_value(&mut fixed, false, "Window Width");
+    ui.horizontal(|ui| {
+        ui.radio_value(&mut fixed, true, "Fixed Columns:");
+        let mut columns = if fixed {
+            app.wrap_columns
+        } else {
+            DEFAULT_WRAP_COLUMNS
+        };
+        let drag = ui.add_enabled(
+            fixed,
+            egui::DragValue::new(&mut columns)
+                .clamp_range(1..=400)
+                .suffix(" full-width"),
+        );
+        if drag.changed() {
+            app.wrap_columns = columns;
+        }
+    });
+    if fixed != (app.wrap_columns > 0) {
+        app.wrap_columns = if fixed { DEFAULT_WRAP_COLUMNS } else { 0 };
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Rulers:");
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut app.rulers_input)
+                .hint_text("20, 40")
+                .desired_width(100.0),
+        );
+        if response.changed() {
+            app.rulers = parse_rulers(&app.rulers_input);
+        }
+    });
+}
+
+// 選択範囲を傍点の記法で囲む
+fn add_emphasis(ctx: &Context, app: &mut TxtEditorApp) {
+    let range = app.selection_range.clone();
+    if range.is_empty() {
+        return;
+    }
+    let text = app.file_contents.char_range(range.clone()).to_string();
+    app.replace_range(ctx, range, &emphasis::wrap(&text));
+    app.focus_editor = true;
+}
+
+fn open_ruby_popup(app: &mut TxtEditorApp) {
+    app.ruby_target = app.selection_range.clone();
+    app.ruby_reading.clear();
+    app.ruby_popup = true;
+}
+
+// 選択範囲を親文字としてルビの記法で囲む
+fn ruby_popup(ctx: &Context, app: &mut TxtEditorApp) {
+    egui::Window::new("Insert Ruby").show(ctx, |ui| {
+        if app.ruby_target.is_empty() {
+            ui.label("Select the text to annotate first.");
+            if ui.button("Close").clicked() {
+                app.ruby_popup = false;
+            }
+            return;
+        }
+
+        let base = app
+            .file_contents
+            .char_range(app.ruby_target.clone())
+            .to_string();
+        ui.label(format!("Base text: {}", base));
+        ui.label("Reading:");
+        let response = ui.text_edit_singleline(&mut app.ruby_reading);
+        response.request_focus();
+
+        let reading = app.ruby_reading.trim().to_string();
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        ui.horizontal(|ui| {
+            let insert = ui.add_enabled(!reading.is_empty(), egui::Button::new("Insert"));
+            if !reading.is_empty() && (insert.clicked() || submitted) {
+                app.replace_range(ctx, app.ruby_target.clone(), &ruby::wrap(&base, &reading));
+                app.focus_editor = true;
+                app.ruby_popup = false;
+            }
+            if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(Key::Escape)) {
+                app.ruby_popup = false;
+            }
+        });
+    });
 }
@@ -0,0 +1,114 @@
+use crate::app::TxtEditorApp;
+use crate::text::convert::TextConversion;
+use crate::ui::utils::{changed_lines, changes_grid};
+use eframe::egui::{self, ComboBox, Context, ScrollArea, TextBuffer};
+use std::ops::Range;
+
+// 変換の確認（選択範囲がなければファイル全体を変換する）
+pub struct ConversionPreview {
+    pub conversion: TextConversion,
+    pub range: Range<usize>,
+    pub converted: String,
+    // 変わる行だけを（行番号、変換前、変換後）で並べる
+    pub changes: Vec<(usize, String, String)>,
+}
+
+impl ConversionPreview {
+    pub fn new(app: &TxtEditorApp, conversion: TextConversion) -> Self {
+        let range = if app.selection_range.is_empty() {
+            0..app.file_contents.chars().count()
+        } else {
+            app.selection_range.clone()
+        };
+        let mut preview = Self {
+            conversion,
+            range,
+            converted: String::new(),
+            changes: Vec::new(),
+        };
+        preview.update(&app.file_contents);
+        preview
+    }
+
+    fn update(&mut self, text: &str) {
+        let end = self.range.end.min(text.chars().count());
+        self.range = self.range.start.min(end)..end;
+        let original = text.char_range(self.range.clone());
+        self.converted = self.conversion.apply(original);
+
+        // 変換は行をまたがないので、行ごとに比べられる
+        let first_line = text.char_range(0..self.range.start).matches('\n').count() + 1;
+        self.changes = changed_lines(original, &self.converted, first_line);
+    }
+}
+
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(preview) = &mut app.conversion_preview else {
+        return;
+    };
+    let mut open = true;
+    let mut apply = false;
+    let mut close = false;
+    let whole_file =
+        preview.range.start == 0 && preview.range.end == app.file_contents.chars().count();
+
+    egui::Window::new("Convert Text")
+        .open(&mut open)
+        .default_width(480.0)
+        .show(ctx, |ui| {
+            let previous = preview.conversion;
+            ComboBox::from_id_source("text_conversion")
+                .selected_text(preview.conversion.label())
+                .width(320.0)
+                .show_ui(ui, |ui| {
+                    for conversion in TextConversion::ALL {
+                        ui.selectable_value(
+                            &mut preview.conversion,
+                            conversion,
+                            conversion.label(),
+                        );
+                    }
+                });
+            if preview.conversion != previous {
+                preview.update(&app.file_contents);
+            }
+
+            ui.label(if whole_file {
+                "Scope: whole file".to_string()
+            } else {
+                format!("Scope: selection ({} characters)", preview.range.len())
+            });
+            ui.separator();
+
+            ui.label(format!("{} line(s) will change", preview.changes.len()));
+            ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                changes_grid(ui, "conversion_changes", &preview.changes);
+            });
+            ui.separator();
+            let changed = !preview.changes.is_empty();
+            ui.horizontal(|ui| {
+                if !changed {
+                    ui.label("Nothing to convert.");
+                }
+                if ui
+                    .add_enabled(changed, egui::Button::new("Apply"))
+                    .clicked()
+                {
+                    apply = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    if apply {
+        // 確認中に編集されていても、適用する時点の文書から変換し直す
+        let mut preview = app.conversion_preview.take().unwrap();
+        preview.update(&app.file_contents);
+        app.replace_range(ctx, preview.range, &preview.converted);
+        app.focus_editor = true;
+    } else if !open || close {
+        app.conversion_preview = None;
+    }
+}
@@ -0,0 +1,222 @@
+use crate::app::TxtEditorApp;
+use crate::document;
+use crate::text::dialect::{convert, Dialect, Issue, LineSpacing};
+use crate::text::genko::collect_text_files;
+use eframe::egui::{self, ComboBox, Context, Grid, ScrollArea};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 投稿サイトの記法の変換ダイアログ
+pub struct DialectConversion {
+    pub targets: Vec<PathBuf>,
+    pub from: Dialect,
+    pub to: Dialect,
+    pub spacing: LineSpacing,
+    // 変換後の報告（書き出したファイルごとの変換できなかった記法）
+    pub report: Option<Vec<(PathBuf, Vec<Issue>)>>,
+    pub error: Option<String>,
+    // 元のファイルを書き換える前の確認中か
+    pub confirm_in_place: bool,
+    // 書き換えた後は、二重に変換しないよう同じダイアログでは書き換えない
+    pub converted_in_place: bool,
+}
+
+impl DialectConversion {
+    pub fn new(targets: Vec<PathBuf>) -> Self {
+        Self {
+            targets,
+            from: Dialect::Kakuyomu,
+            to: Dialect::Narou,
+            spacing: LineSpacing::Keep,
+            report: None,
+            error: None,
+            confirm_in_place: false,
+            converted_in_place: false,
+        }
+    }
+}
+
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(dialog) = &mut app.dialect_conversion else {
+        return;
+    };
+    let mut open = true;
+    let mut run: Option<Option<PathBuf>> = None;
+    let mut jump: Option<(PathBuf, usize)> = None;
+
+    egui::Window::new("Convert Dialect")
+        .open(&mut open)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.label(format!("{} item(s) selected", dialog.targets.len()));
+            Grid::new("dialect_options").num_columns(2).show(ui, |ui| {
+                ui.label("From");
+                dialect_combo(ui, "dialect_from", &mut dialog.from, false);
+                ui.end_row();
+                ui.label("To");
+                dialect_combo(ui, "dialect_to", &mut dialog.to, true);
+                ui.end_row();
+                ui.label("Line spacing");
+                ComboBox::from_id_source("dialect_spacing")
+                    .selected_text(dialog.spacing.label())
+                    .show_ui(ui, |ui| {
+                        for spacing in LineSpacing::ALL {
+                            ui.selectable_value(&mut dialog.spacing, spacing, spacing.label());
+                        }
+                    });
+                ui.end_row();
+            });
+
+            if let Some(error) = &dialog.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+            if dialog.confirm_in_place {
+                ui.label("The selected files will be overwritten. Continue?");
+                ui.horizontal(|ui| {
+                    if ui.button("Convert").clicked() {
+                        dialog.confirm_in_place = false;
+                        run = Some(None);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        dialog.confirm_in_place = false;
+                    }
+                });
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                let enabled = !dialog.confirm_in_place && !dialog.converted_in_place;
+                let mut button = ui.add_enabled(enabled, egui::Button::new("Convert in Place"));
+                if dialog.converted_in_place {
+                    button = button.on_disabled_hover_text("The files have already been converted");
+                }
+                if button.clicked() {
+                    dialog.confirm_in_place = true;
+                }
+                if ui.button("Export to Folder...").clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        run = Some(Some(folder));
+                    }
+                }
+            });
+
+            if let Some(report) = &dialog.report {
+                ui.separator();
+                let count: usize = report.iter().map(|(_, issues)| issues.len()).sum();
+                ui.label(format!(
+                    "Converted {} file(s), {} construct(s) could not be converted",
+                    report.len(),
+                    count
+                ));
+                ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+                    for (path, issues) in report {
+                        for issue in issues {
+                            let name = path.file_name().unwrap_or_default().to_string_lossy();
+                            let text = format!("{}:{}  {}", name, issue.line, issue.message);
+                            if ui.link(text).clicked() {
+                                jump = Some((path.clone(), issue.line));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+    if let Some(destination) = run {
+        run_conversion(app, destination.as_deref());
+    }
+    if let Some((path, line)) = jump {
+        if app.selected_file.as_ref() != Some(&path) {
+            app.open_file(&path);
+        }
+        app.go_to_line(line);
+    }
+    if !open {
+        app.dialect_conversion = None;
+    }
+}
+
+fn dialect_combo(ui: &mut egui::Ui, id: &str, value: &mut Dialect, allow_plain: bool) {
+    ComboBox::from_id_source(id)
+        .selected_text(value.label())
+        .show_ui(ui, |ui| {
+            for dialect in Dialect::ALL {
+                if allow_plain || dialect != Dialect::Plain {
+                    ui.selectable_value(value, dialect, dialect.label());
+                }
+            }
+        });
+}
+
+// destination がなければ元のファイルを書き換える
+// あればフォルダの構成を保ったまま書き出す
+fn run_conversion(app: &mut TxtEditorApp, destination: Option<&Path>) {
+    let saved = app.save_file_if_modified();
+    let Some(dialog) = &mut app.dialect_conversion else {
+        return;
+    };
+    // 開いているファイルの変更を保存できないうちは書き換えない
+    if !saved {
+        dialog.error = Some(format!(
+            "Save the open file first: {}",
+            app.save_error.as_deref().unwrap_or_default()
+        ));
+        return;
+    }
+
+    let mut report = Vec::new();
+    let mut errors = Vec::new();
+    for target in &dialog.targets {
+        let base = target.parent().unwrap_or(target);
+        let mut files = Vec::new();
+        collect_text_files(target, &mut files);
+        for file in files {
+            let output = match destination {
+                Some(folder) => folder.join(file.strip_prefix(base).unwrap_or(&file)),
+                None => file.clone(),
+            };
+            match convert_file(&file, &output, dialog) {
+                Ok(issues) => report.push((output, issues)),
+                Err(err) => errors.push(format!("{}: {}", file.display(), err)),
+            }
+        }
+    }
+    dialog.report = Some(report);
+    if destination.is_none() {
+        dialog.converted_in_place = true;
+    }
+    dialog.error = (!errors.is_empty()).then(|| errors.join("\n"));
+
+    // 開いているファイルを書き換えた場合は読み直す
+    if destination.is_none() {
+        let converted = app.selected_file.clone().filter(|path| {
+            app.dialect_conversion
+                .as_ref()
+                .is_some_and(|dialog| dialog.targets.iter().any(|t| path.starts_with(t)))
+        });
+        if let Some(path) = converted {
+            app.open_file(&path);
+        }
+    }
+    app.refresh_file_list();
+}
+
+// 元のファイルの文字コードと改行コードで保存する
+fn convert_file(
+    source: &Path,
+    output: &Path,
+    dialog: &DialectConversion,
+) -> Result<Vec<Issue>, String> {
+    let loaded = document::load(source).map_err(|err| err.to_string())?;
+    let conversion = convert(&loaded.text, dialog.from, dialog.to, dialog.spacing);
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    document::save(
+        output,
+        &conversion.text,
+        loaded.encoding,
+        loaded.line_ending,
+    )?;
+    Ok(conversion.issues)
+}
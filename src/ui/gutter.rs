@@ -0,0 +1,136 @@
+use crate::app::TxtEditorApp;
+use eframe::egui::widgets::text_edit::TextEditOutput;
+use eframe::egui::{Align2, Color32, FontId, Id, Pos2, Rect, Sense, TextStyle, Ui};
+use std::ops::Range;
+
+const PADDING: f32 = 8.0;
+
+// 行番号の欄の幅（行番号を表示しないときは 0）
+pub fn width(ui: &Ui, app: &TxtEditorApp) -> f32 {
+    if !app.show_line_numbers {
+        return 0.0;
+    }
+    // 毎フレーム文書全体を走査しないよう、差分で更新している統計の行数を使う
+    let digits = app.stats.line_count().to_string().len().max(3);
+    let font = TextStyle::Monospace.resolve(ui.style());
+    let digit_width = ui.fonts(|fonts| fonts.glyph_width(&font, '0'));
+    digits as f32 * digit_width + PADDING * 2.0
+}
+
+// 行ごとの表示上の行（折り返した行）と、論理行の番号・先頭の文字位置
+struct RowInfo {
+    rect: Rect,
+    line: usize,
+    first_in_line: bool,
+    visual: usize,
+}
+
+fn rows(output: &TextEditOutput) -> (Vec<RowInfo>, Vec<usize>) {
+    let mut rows = Vec::with_capacity(output.galley.rows.len());
+    let mut line_starts = vec![0];
+    let mut line = 0;
+    let mut first_in_line = true;
+    let mut char_index = 0;
+    for (visual, row) in output.galley.rows.iter().enumerate() {
+        rows.push(RowInfo {
+            rect: row.rect.translate(output.galley_pos.to_vec2()),
+            line,
+            first_in_line,
+            visual,
+        });
+        char_index += row.char_count_including_newline();
+        first_in_line = row.ends_with_newline;
+        if row.ends_with_newline {
+            line += 1;
+            line_starts.push(char_index);
+        }
+    }
+    line_starts.push(char_index);
+    (rows, line_starts)
+}
+
+// 行番号と現在行の強調を描き、行番号のクリック・ドラッグで選ぶ行の範囲（文字位置）を返す
+pub fn show(
+    ui: &mut Ui,
+    app: &mut TxtEditorApp,
+    output: &TextEditOutput,
+    gutter_width: f32,
+) -> Option<Range<usize>> {
+    let (rows, line_starts) = rows(output);
+    let editor = output.response.rect;
+    let current_line = output
+        .cursor_range
+        .map_or(app.cursor_line.saturating_sub(1), |range| {
+            range.primary.pcursor.paragraph
+        });
+    let clip = ui.clip_rect();
+    let visuals = ui.visuals().clone();
+
+    // 現在行は文字の上に薄く重ねて塗る
+    if app.highlight_current_line {
+        let fill = if visuals.dark_mode {
+            Color32::from_white_alpha(10)
+        } else {
+            Color32::from_black_alpha(10)
+        };
+        for row in rows.iter().filter(|row| row.line == current_line) {
+            let rect = Rect::from_x_y_ranges(editor.x_range(), row.rect.y_range());
+            ui.painter().rect_filled(rect, 0.0, fill);
+        }
+    }
+
+    if gutter_width <= 0.0 {
+        return None;
+    }
+    let gutter = Rect::from_min_max(
+        Pos2::new(editor.left() - gutter_width, editor.top()),
+        Pos2::new(editor.left(), editor.bottom()),
+    );
+    let font = FontId::monospace(TextStyle::Monospace.resolve(ui.style()).size * 0.9);
+    for row in &rows {
+        if row.rect.bottom() < clip.top() || row.rect.top() > clip.bottom() {
+            continue;
+        }
+        let (text, color) = if row.first_in_line {
+            let color = if row.line == current_line {
+                visuals.strong_text_color()
+            } else {
+                visuals.weak_text_color()
+            };
+            ((row.line + 1).to_string(), color)
+        } else if app.visual_line_numbers {
+            (
+                format!("({})", row.visual + 1),
+                visuals.weak_text_color().gamma_multiply(0.6),
+            )
+        } else {
+            continue;
+        };
+        let pos = Pos2::new(gutter.right() - PADDING, row.rect.center().y);
+        ui.painter()
+            .text(pos, Align2::RIGHT_CENTER, text, font.clone(), color);
+    }
+
+    // クリックで行全体を、ドラッグで複数行を選択する
+    let response = ui.interact(gutter, Id::new("line_gutter"), Sense::click_and_drag());
+    let pointer = response.interact_pointer_pos()?;
+    let line = rows
+        .iter()
+        .find(|row| pointer.y < row.rect.bottom())
+        .or(rows.last())
+        .map(|row| row.line)?;
+    if response.drag_started() || response.clicked() || app.gutter_anchor.is_none() {
+        app.gutter_anchor = Some(line);
+    }
+    let anchor = app.gutter_anchor.unwrap_or(line);
+    if response.drag_stopped() || response.clicked() {
+        app.gutter_anchor = None;
+    }
+    if !(response.clicked() || response.dragged() || response.drag_stopped()) {
+        return None;
+    }
+    let (first, last) = (anchor.min(line), anchor.max(line));
+    let start = line_starts[first];
+    let end = line_starts[(last + 1).min(line_starts.len() - 1)];
+    Some(start..end)
+}
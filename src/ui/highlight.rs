@@ -0,0 +1,214 @@
+use crate::text::columns::char_width;
+use crate::text::emphasis::find_emphasis;
+use crate::text::lint::Diagnostic;
+use crate::text::ruby::find_rubies;
+use crate::text::whitespace::{mixed_indentation, trailing_whitespace};
+use eframe::egui::text::{LayoutJob, LayoutSection, TextFormat};
+use eframe::egui::{Color32, FontId, Stroke, Ui};
+use std::ops::Range;
+
+// 色分けする範囲（後に追加した範囲の指定が優先される）
+pub struct Span {
+    pub range: Range<usize>,
+    pub color: Option<Color32>,
+    pub background: Option<Color32>,
+    pub underline: Option<Stroke>,
+}
+
+impl Span {
+    pub fn color(range: Range<usize>, color: Color32) -> Self {
+        Self {
+            range,
+            color: Some(color),
+            background: None,
+            underline: None,
+        }
+    }
+}
+
+const MARKUP_COLOR: Color32 = Color32::GRAY;
+const RUBY_COLOR: Color32 = Color32::from_rgb(90, 150, 230);
+const EMPHASIS_COLOR: Color32 = Color32::from_rgb(230, 140, 60);
+const LINT_COLOR: Color32 = Color32::from_rgb(220, 60, 60);
+const WHITESPACE_COLOR: Color32 = Color32::from_rgba_premultiplied(120, 40, 40, 90);
+
+// ルビの記法（区切り・括弧は灰色、読みは青、親文字は下線）
+pub fn ruby_spans(text: &str, spans: &mut Vec<Span>) {
+    let mut offset = 0;
+    for line in text.split('\n') {
+        for ruby in find_rubies(line) {
+            let shift = |range: Range<usize>| offset + range.start..offset + range.end;
+            spans.push(Span::color(
+                shift(ruby.range.start..ruby.base.start),
+                MARKUP_COLOR,
+            ));
+            spans.push(Span {
+                range: shift(ruby.base.clone()),
+                color: None,
+                background: None,
+                underline: Some(Stroke::new(1.0, RUBY_COLOR)),
+            });
+            spans.push(Span::color(
+                shift(ruby.base.end..ruby.ruby.start),
+                MARKUP_COLOR,
+            ));
+            spans.push(Span::color(shift(ruby.ruby.clone()), RUBY_COLOR));
+            spans.push(Span::color(
+                shift(ruby.ruby.end..ruby.range.end),
+                MARKUP_COLOR,
+            ));
+        }
+        offset += line.len() + 1;
+    }
+}
+
+// 傍点の記法（括弧は灰色、対象の文字はオレンジ）
+pub fn emphasis_spans(text: &str, spans: &mut Vec<Span>) {
+    let mut offset = 0;
+    for line in text.split('\n') {
+        for emphasis in find_emphasis(line) {
+            let shift = |range: Range<usize>| offset + range.start..offset + range.end;
+            spans.push(Span::color(
+                shift(emphasis.range.start..emphasis.text.start),
+                MARKUP_COLOR,
+            ));
+            spans.push(Span::color(shift(emphasis.text.clone()), EMPHASIS_COLOR));
+            spans.push(Span::color(
+                shift(emphasis.text.end..emphasis.range.end),
+                MARKUP_COLOR,
+            ));
+        }
+        offset += line.len() + 1;
+    }
+}
+
+// 校正の指摘（赤い下線）
+pub fn lint_spans(diagnostics: &[Diagnostic], spans: &mut Vec<Span>) {
+    for diagnostic in diagnostics {
+        spans.push(Span {
+            range: diagnostic.range.clone(),
+            color: None,
+            background: None,
+            underline: Some(Stroke::new(1.5, LINT_COLOR)),
+        });
+    }
+}
+
+// 行末の空白と、混在した行頭の空白（背景を塗る）
+pub fn whitespace_spans(text: &str, spans: &mut Vec<Span>) {
+    let mut offset = 0;
+    for line in text.split('\n') {
+        let ranges = [trailing_whitespace(line), mixed_indentation(line)];
+        for range in ranges.into_iter().flatten() {
+            spans.push(Span {
+                range: offset + range.start..offset + range.end,
+                color: None,
+                background: Some(WHITESPACE_COLOR),
+                underline: None,
+            });
+        }
+        offset += line.len() + 1;
+    }
+}
+
+// 文字幅（半角は全角の半分）どおりに並ぶように、文字の間隔を調整する
+// egui は区間の中の文字の間にだけ letter spacing を入れるので、調整量の同じ文字ごとに
+// 区間を分け、区間の境目の間隔は leading_space で補う（改行で区間を区切り、行頭は詰める）
+pub fn align_to_columns(ui: &Ui, job: &mut LayoutJob, full_width: f32) {
+    let sections = std::mem::take(&mut job.sections);
+    let mut aligned = Vec::with_capacity(sections.len());
+    ui.fonts(|fonts| {
+        let mut leading = 0.0;
+        for section in sections {
+            let font = &section.format.font_id;
+            let mut push = |range: Range<usize>, extra: f32, leading: f32| {
+                let mut format = section.format.clone();
+                format.extra_letter_spacing = extra;
+                aligned.push(LayoutSection {
+                    leading_space: leading,
+                    byte_range: range,
+                    format,
+                });
+            };
+            let mut start = section.byte_range.start;
+            let mut current: Option<f32> = None;
+            for (offset, c) in job.text[section.byte_range.clone()].char_indices() {
+                let index = section.byte_range.start + offset;
+                if c == '\n' {
+                    push(start..index + 1, current.unwrap_or(0.0), leading);
+                    start = index + 1;
+                    current = None;
+                    leading = 0.0;
+                    continue;
+                }
+                let extra = char_width(c) as f32 * full_width / 2.0 - fonts.glyph_width(font, c);
+                match current {
+                    Some(value) if (value - extra).abs() < 0.01 => {}
+                    Some(value) => {
+                        push(start..index, value, leading);
+                        leading = value;
+                        start = index;
+                        current = Some(extra);
+                    }
+                    None => current = Some(extra),
+                }
+            }
+            if let Some(value) = current {
+                push(start..section.byte_range.end, value, leading);
+                leading = value;
+            } else if start < section.byte_range.end {
+                push(start..section.byte_range.end, 0.0, leading);
+            }
+        }
+    });
+    job.sections = aligned;
+}
+
+// 範囲の指定から TextEdit 用のレイアウトを作る
+pub fn layout_job(ui: &Ui, text: &str, font: FontId, spans: &[Span]) -> LayoutJob {
+    let default_color = ui.visuals().text_color();
+    let mut job = LayoutJob::default();
+
+    let mut boundaries: Vec<usize> = spans
+        .iter()
+        .flat_map(|span| [span.range.start, span.range.end])
+        .chain([0, text.len()])
+        .filter(|&i| i <= text.len() && text.is_char_boundary(i))
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    // 開始位置の順に範囲を有効にし、終わった範囲を外していく
+    let mut order: Vec<usize> = (0..spans.len()).collect();
+    order.sort_by_key(|&i| spans[i].range.start);
+    let mut next = 0;
+    let mut active: Vec<usize> = Vec::new();
+
+    for segment in boundaries.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        while next < order.len() && spans[order[next]].range.start <= start {
+            active.push(order[next]);
+            next += 1;
+        }
+        active.retain(|&i| spans[i].range.end > start);
+        active.sort_unstable();
+
+        let mut format = TextFormat::simple(font.clone(), default_color);
+        for span in active.iter().map(|&i| &spans[i]) {
+            if let Some(color) = span.color {
+                format.color = color;
+            }
+            if let Some(background) = span.background {
+                format.background = background;
+            }
+            if let Some(underline) = span.underline {
+                format.underline = underline;
+            }
+        }
+        job.append(&text[start..end], 0.0, format);
+    }
+    if job.sections.is_empty() {
+        job.append("", 0.0, TextFormat::simple(font, default_color));
+    }
+    job
+}
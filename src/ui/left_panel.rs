@@ -1,22 +1,50 @@
 use crate::app::TxtEditorApp;
-use crate::file_operations::{get_txt_files_and_dirs_in_directory, move_to_trash};
-use crate::ui::utils::{add_text_file, create_folder};
-use eframe::egui::{self, CollapsingHeader, Color32, Context, SidePanel};
+use crate::batch_rename::BatchRenameDialog;
+use crate::clipboard::{ClipboardMode, ConflictResolution, TreeClipboard};
+use crate::document;
+use crate::file_operations::concatenate_text_files;
+use crate::tags::is_valid_tag;
+use crate::text::genko::ManuscriptReport;
+use crate::text::html::to_html;
+use crate::tree::{collect_extensions, move_in_manual_order, symlink_target, SortMode, PAGE_SIZE};
+use crate::ui::aozora::{import_file, AozoraExport};
+use crate::ui::dialect::DialectConversion;
+use crate::ui::utils::{add_new_file, create_folder};
+use crate::ui::vertical_preview::PreviewMode;
+use eframe::egui::{
+    self, CollapsingHeader, Color32, ComboBox, Context, Id, Key, Label, Modifiers, Response,
+    RichText, ScrollArea, Sense, SidePanel, Stroke,
+};
+use std::collections::BTreeSet;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+// ドラッグ中のツリー項目
+struct TreeDragPayload(Vec<PathBuf>);
 
 // フォルダの表示
 fn display_directory(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp) {
     if path.is_dir() {
-        let dir_name = path.file_name().unwrap().to_string_lossy().to_string();
-        let is_selected = Some(path) == app.selected_item.as_ref();
+        if is_link_loop(app, path) {
+            display_link_loop(ui, path, app);
+            return;
+        }
+        app.rendered_items.push(path.clone());
+        let dir_name = display_name(path);
+        let is_selected = app.selected_items.contains(path);
 
         if is_selected {
             ui.style_mut().visuals.widgets.noninteractive.bg_fill = Color32::YELLOW;
         }
 
-        let header = CollapsingHeader::new(dir_name.clone()).default_open(false);
+        // 絞り込み中は一致する項目を含むフォルダを開いて表示
+        let is_expanded =
+            app.expanded_dirs.contains(path) || !app.tree_options.filter.trim().is_empty();
+        let header = CollapsingHeader::new(with_tags(dir_name, path, app))
+            .id_source(path)
+            .open(Some(is_expanded));
+        // 子要素は開いているときだけ読み込まれる
         let response = header.show(ui, |ui| display_entries(ui, path, app));
 
         if is_selected {
@@ -25,105 +53,642 @@ fn display_directory(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp)
         }
 
         if response.header_response.clicked() {
-            app.selected_item = Some(path.clone());
-            app.selected_file = None;
+            toggle_expanded(app, path);
+            // 保存できなかった変更があるときは文書を閉じない
+            if update_selection(ui, app, path) && app.save_file_if_modified() {
+                app.clear_document();
+            }
         }
+        scroll_to_if_selected(app, path, &response.header_response);
+
+        let drag_response = ui.interact(
+            response.header_response.rect,
+            Id::new(("tree_drag", path)),
+            Sense::drag(),
+        );
+        drag_response.dnd_set_drag_payload(TreeDragPayload(drag_targets(app, path)));
+        handle_drop_target(ui, &response.header_response, path, app);
+
+        response
+            .header_response
+            .context_menu(|ui| item_context_menu(ui, path, app));
     } else {
         display_file(ui, path, app);
     }
 }
 
-// フォルダ内のエントリ表示
-fn display_entries(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp) {
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.flatten() {
-            let entry_path = entry.path();
-            if let Some(file_name) = entry_path.file_name() {
-                if !file_name.to_string_lossy().starts_with('.') {
-                    display_directory(ui, &entry_path, app);
-                }
-            }
-        }
+// フォルダ内のエントリ表示（多い場合は PAGE_SIZE ずつ表示）
+fn display_entries(ui: &mut egui::Ui, path: &Path, app: &mut TxtEditorApp) {
+    // シンボリックリンクの循環を検出するため、表示中の祖先フォルダの実体を記録する
+    let limit = app.page_limits.get(path).copied().unwrap_or(PAGE_SIZE);
+    let cached = app.cached_dir(path);
+    let canonical = cached.canonical.clone();
+    let total = cached.children.len();
+    let page = cached.children[..total.min(limit)].to_vec();
+    app.tree_ancestors.push(canonical);
+    for entry_path in &page {
+        display_directory(ui, entry_path, app);
+    }
+    if total > limit
+        && ui
+            .button(format!("Show more ({} remaining)", total - limit))
+            .clicked()
+    {
+        app.page_limits
+            .insert(path.to_path_buf(), limit + PAGE_SIZE);
+    }
+
+    app.tree_ancestors.pop();
+}
+
+// 祖先フォルダを指すシンボリックリンクか
+fn is_link_loop(app: &TxtEditorApp, path: &Path) -> bool {
+    path.is_symlink()
+        && fs::canonicalize(path).is_ok_and(|target| app.tree_ancestors.contains(&target))
+}
+
+// 循環するリンクは展開せずに表示する
+fn display_link_loop(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp) {
+    app.rendered_items.push(path.clone());
+    let text = format!("{} (link loop)", display_name(path));
+    let response = ui.add(
+        Label::new(RichText::new(text).color(Color32::GRAY))
+            .selectable(false)
+            .sense(Sense::click()),
+    );
+    if response.clicked() {
+        update_selection(ui, app, path);
+    }
+    scroll_to_if_selected(app, path, &response);
+    response.context_menu(|ui| item_context_menu(ui, path, app));
+}
+
+// 表示名（シンボリックリンクはリンク先も表示）
+fn display_name(path: &Path) -> String {
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+    match symlink_target(path) {
+        Some(target) => format!("{} → {}", name, target.display()),
+        None => name,
     }
 }
 
 // ファイルの表示
 fn display_file(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp) {
-    let file_name = path.file_name().unwrap().to_string_lossy().to_string();
-    if !file_name.starts_with('.') {
-        let is_selected = Some(path) == app.selected_item.as_ref();
-        let label = if is_selected {
-            ui.colored_label(Color32::YELLOW, file_name.clone())
-        } else {
-            ui.label(file_name.clone())
-        };
+    let file_name = display_name(path);
+    app.rendered_items.push(path.clone());
+    let is_selected = app.selected_items.contains(path);
+    let label = with_tags(file_name, path, app);
+    let text = if is_selected {
+        RichText::new(label).color(Color32::YELLOW)
+    } else {
+        RichText::new(label)
+    };
+    let response = ui.add(
+        Label::new(text)
+            .selectable(false)
+            .sense(Sense::click_and_drag()),
+    );
+
+    if response.clicked() && update_selection(ui, app, path) {
+        app.open_file(path);
+    }
+    scroll_to_if_selected(app, path, &response);
+
+    response.dnd_set_drag_payload(TreeDragPayload(drag_targets(app, path)));
+    if let Some(parent_dir) = path.parent() {
+        handle_drop_target(ui, &response, parent_dir, app);
+    }
+
+    response.context_menu(|ui| item_context_menu(ui, path, app));
+}
+
+// タグ付きの表示名
+fn with_tags(name: String, path: &Path, app: &TxtEditorApp) -> String {
+    match app.tags.tags_for(path) {
+        Some(tags) => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            format!("{} [{}]", name, tags.join(", "))
+        }
+        None => name,
+    }
+}
 
-        let response = label;
+// クリック時の選択（Ctrl で追加・解除、Shift で範囲選択）
+// 通常のクリックなら true を返す
+fn update_selection(ui: &egui::Ui, app: &mut TxtEditorApp, path: &PathBuf) -> bool {
+    let modifiers = ui.input(|i| i.modifiers);
+    app.tree_has_focus = true;
 
-        if response.clicked() {
-            select_file(app, path);
+    if modifiers.command {
+        if let Some(index) = app.selected_items.iter().position(|item| item == path) {
+            app.selected_items.remove(index);
+        } else {
+            app.selected_items.push(path.clone());
         }
+        app.selected_item = Some(path.clone());
+        false
+    } else if modifiers.shift {
+        let anchor = app
+            .selected_item
+            .as_ref()
+            .and_then(|item| app.visible_items.iter().position(|p| p == item));
+        let target = app.visible_items.iter().position(|p| p == path);
+        if let (Some(anchor), Some(target)) = (anchor, target) {
+            let range = anchor.min(target)..=anchor.max(target);
+            app.selected_items = app.visible_items[range].to_vec();
+        } else {
+            app.selected_items = vec![path.clone()];
+            app.selected_item = Some(path.clone());
+        }
+        false
+    } else {
+        app.selected_items = vec![path.clone()];
+        app.selected_item = Some(path.clone());
+        true
+    }
+}
 
-        response.context_menu(|ui| {
-            if ui.button("Open in RightPanel").clicked() {
-                app.right_panel_file = Some(path.clone());
-                app.right_panel_contents = std::fs::read_to_string(path)
-                    .unwrap_or_else(|_| "Failed to read file".to_string());
+// ドラッグ・メニュー操作の対象（選択中の項目なら選択全体）
+fn drag_targets(app: &TxtEditorApp, path: &PathBuf) -> Vec<PathBuf> {
+    if app.selected_items.contains(path) {
+        app.selected_items.clone()
+    } else {
+        vec![path.clone()]
+    }
+}
+
+// ファイル・フォルダ共通のコンテキストメニュー
+fn item_context_menu(ui: &mut egui::Ui, path: &PathBuf, app: &mut TxtEditorApp) {
+    if !app.selected_items.contains(path) {
+        app.selected_items = vec![path.clone()];
+    }
+    app.selected_item = Some(path.clone());
+    let targets = app.selected_items.clone();
+
+    if targets.len() > 1 {
+        ui.label(format!("{} items selected", targets.len()));
+        ui.separator();
+    } else {
+        if path.is_file() && ui.button("Open in RightPanel").clicked() {
+            app.right_panel_file = Some(path.clone());
+            app.right_panel_contents =
+                std::fs::read_to_string(path).unwrap_or_else(|_| "Failed to read file".to_string());
+            ui.close_menu();
+        }
+        if ui.button("Rename").clicked() {
+            start_rename(app, path);
+            ui.close_menu();
+        }
+        if app.tree_options.sort_mode == SortMode::Manual {
+            if ui.button("Move Up").clicked() {
+                move_in_order(app, path, -1);
                 ui.close_menu();
             }
-            if ui.button("Rename").clicked() {
-                app.rename_popup = true;
-                app.rename_target = Some(path.clone());
-                app.new_name = if file_name.ends_with(".txt") {
-                    file_name.trim_end_matches(".txt").to_string()
-                } else {
-                    file_name.to_string()
-                };
+            if ui.button("Move Down").clicked() {
+                move_in_order(app, path, 1);
                 ui.close_menu();
             }
-            if ui.button("Delete").clicked() {
-                delete_file(app, path);
-                ui.close_menu();
+        }
+    }
+    if ui.button("Delete").clicked() {
+        app.delete_items(&targets);
+        ui.close_menu();
+    }
+    ui.separator();
+    if ui.button("Cut").clicked() {
+        app.tree_clipboard = Some(TreeClipboard {
+            paths: targets.clone(),
+            mode: ClipboardMode::Cut,
+        });
+        ui.close_menu();
+    }
+    if ui.button("Copy").clicked() {
+        app.tree_clipboard = Some(TreeClipboard {
+            paths: targets.clone(),
+            mode: ClipboardMode::Copy,
+        });
+        ui.close_menu();
+    }
+    let can_paste = app.tree_clipboard.is_some() && app.file_transfer.is_none();
+    if ui
+        .add_enabled(can_paste, egui::Button::new("Paste"))
+        .clicked()
+    {
+        paste_into(app, &target_directory(path));
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(app.file_transfer.is_none(), egui::Button::new("Duplicate"))
+        .clicked()
+    {
+        for target in &targets {
+            duplicate_item(app, target);
+        }
+        ui.close_menu();
+    }
+    if ui.button("Move To...").clicked() {
+        if let Some(target_dir) = rfd::FileDialog::new().pick_folder() {
+            for target in &targets {
+                app.queue_transfer(target.clone(), target_dir.clone(), ClipboardMode::Cut);
             }
-            if ui.button("Add Text File").clicked() {
-                add_text_file_to_selected_directory(app);
+        }
+        ui.close_menu();
+    }
+    if ui.button("Batch Rename...").clicked() {
+        let targets = batch_rename_targets(app, &targets);
+        app.batch_rename = Some(BatchRenameDialog::new(targets));
+        ui.close_menu();
+    }
+    ui.separator();
+    ui.menu_button("Tags", |ui| {
+        if ui.button("Add Tag...").clicked() {
+            app.tag_popup = true;
+            app.new_tag.clear();
+            ui.close_menu();
+        }
+        let current_tags: BTreeSet<String> = targets
+            .iter()
+            .filter_map(|target| app.tags.tags_for(target))
+            .flatten()
+            .cloned()
+            .collect();
+        for tag in current_tags {
+            if ui.button(format!("Remove \"{}\"", tag)).clicked() {
+                for target in &targets {
+                    app.tags.remove(target, &tag);
+                }
+                if let Err(err) = app.tags.save() {
+                    eprintln!("Failed to save tags: {}", err);
+                }
                 ui.close_menu();
             }
-            if ui.button("Add Folder").clicked() {
-                add_folder_to_selected_directory(app);
-                ui.close_menu();
+        }
+    });
+    if ui.button("Export as Single File...").clicked() {
+        if let Some(destination) = rfd::FileDialog::new()
+            .add_filter("Text", &["txt"])
+            .save_file()
+        {
+            if let Err(err) = concatenate_text_files(&targets, &destination) {
+                eprintln!("Failed to export files: {}", err);
             }
-        });
+            app.refresh_file_list();
+        }
+        ui.close_menu();
+    }
+    if targets.len() == 1 && path.is_file() && ui.button("Export as HTML...").clicked() {
+        export_html(app, path);
+        ui.close_menu();
+    }
+    if targets.len() == 1 && path.is_file() {
+        if ui.button("Export as Aozora Text...").clicked() {
+            app.save_file_if_modified();
+            app.aozora_export = Some(AozoraExport::new(path));
+            ui.close_menu();
+        }
+        if ui.button("Convert from Aozora Text").clicked() {
+            if let Some(dir) = path.parent() {
+                import_file(app, path, dir);
+            }
+            ui.close_menu();
+        }
+    }
+    if path.is_dir() && ui.button("Import Aozora Text...").clicked() {
+        if let Some(source) = rfd::FileDialog::new()
+            .add_filter("Text", &["txt"])
+            .pick_file()
+        {
+            import_file(app, &source, path);
+        }
+        ui.close_menu();
+    }
+    if ui.button("Convert Dialect...").clicked() {
+        app.dialect_conversion = Some(DialectConversion::new(targets.clone()));
+        ui.close_menu();
+    }
+    if ui.button("Manuscript Pages...").clicked() {
+        app.manuscript_report = Some(ManuscriptReport::new(&targets, app.exclude_markup));
+        ui.close_menu();
+    }
+    ui.separator();
+    if ui.button("New File").clicked() {
+        add_new_file_to_selected_directory(app);
+        ui.close_menu();
+    }
+    if ui.button("Add Folder").clicked() {
+        add_folder_to_selected_directory(app);
+        ui.close_menu();
     }
 }
 
-// ファイルの選択
-fn select_file(app: &mut TxtEditorApp, path: &PathBuf) {
-    app.selected_item = Some(path.clone());
-    app.selected_file = Some(path.clone());
-    app.file_contents =
-        std::fs::read_to_string(path).unwrap_or_else(|_| "Failed to read file".to_string());
-    app.file_modified = false;
+// ルビ・傍点を反映した HTML に書き出す（縦書きかどうかはプレビューの設定に合わせる）
+fn export_html(app: &mut TxtEditorApp, path: &Path) {
+    app.save_file_if_modified();
+    let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+        return;
+    };
+    let Some(destination) = rfd::FileDialog::new()
+        .add_filter("HTML", &["html"])
+        .set_file_name(&format!("{}.html", stem))
+        .save_file()
+    else {
+        return;
+    };
+    let vertical = app.preview_mode == PreviewMode::Vertical;
+    let result = document::load(path)
+        .and_then(|loaded| fs::write(&destination, to_html(&stem, &loaded.text, vertical)));
+    if let Err(err) = result {
+        eprintln!("Failed to export HTML: {}", err);
+    }
+    app.refresh_file_list();
+}
+
+// 一括リネームの対象（フォルダ単体ならその中身、複数選択なら表示順）
+fn batch_rename_targets(app: &TxtEditorApp, targets: &[PathBuf]) -> Vec<PathBuf> {
+    if let [dir] = targets {
+        if dir.is_dir() {
+            return app.visible_children(dir);
+        }
+    }
+
+    let mut ordered: Vec<PathBuf> = app
+        .visible_items
+        .iter()
+        .filter(|item| targets.contains(item))
+        .cloned()
+        .collect();
+    let hidden: Vec<PathBuf> = targets
+        .iter()
+        .filter(|target| !ordered.contains(target))
+        .cloned()
+        .collect();
+    ordered.extend(hidden);
+    ordered
+}
+
+// 名前変更のポップアップを開く
+fn start_rename(app: &mut TxtEditorApp, path: &Path) {
+    let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+    app.rename_popup = true;
+    app.rename_target = Some(path.to_path_buf());
+    app.new_name = match path.file_stem() {
+        Some(stem) if path.is_file() => stem.to_string_lossy().to_string(),
+        _ => file_name,
+    };
+}
+
+// 手動の並び順での移動
+fn move_in_order(app: &mut TxtEditorApp, path: &Path, offset: isize) {
+    if let Err(err) = move_in_manual_order(path, offset, &app.tree_options) {
+        eprintln!("Failed to save manual order: {}", err);
+    }
+    app.refresh_file_list();
 }
 
-// ファイルの削除
-fn delete_file(app: &mut TxtEditorApp, path: &PathBuf) {
-    if let Err(err) = move_to_trash(path) {
-        eprintln!("Failed to move file to trash: {}", err);
+// フォルダの開閉
+fn toggle_expanded(app: &mut TxtEditorApp, path: &Path) {
+    // 開くときは読み直し、閉じるときは読み込んだ内容を破棄する
+    app.children_cache.remove(path);
+    if app.expanded_dirs.remove(path) {
+        app.page_limits.remove(path);
     } else {
-        app.file_list.retain(|f| f != path);
-        if app.selected_file == Some(path.clone()) {
-            app.selected_file = None;
-            app.file_contents.clear();
+        app.expanded_dirs.insert(path.to_path_buf());
+    }
+}
+
+// キーボード操作で選択が移動したときに表示範囲へスクロール
+fn scroll_to_if_selected(app: &mut TxtEditorApp, path: &Path, response: &Response) {
+    if app.scroll_to_selected && app.selected_item.as_deref() == Some(path) {
+        response.scroll_to_me(None);
+        app.scroll_to_selected = false;
+    }
+}
+
+// キーボードでの選択移動
+fn move_selection_to(app: &mut TxtEditorApp, path: PathBuf) {
+    app.selected_items = vec![path.clone()];
+    app.selected_item = Some(path);
+    app.scroll_to_selected = true;
+}
+
+// ツリーのキーボード操作（ツリーにフォーカスがあるときのみ）
+fn handle_tree_keyboard(ctx: &Context, app: &mut TxtEditorApp) {
+    let popup_open = app.rename_popup
+        || app.new_folder_popup
+        || app.new_file_popup
+        || app.tag_popup
+        || app.batch_rename.is_some()
+        || app.paste_conflict.is_some();
+    if !app.tree_has_focus || popup_open || ctx.memory(|m| m.focused().is_some()) {
+        return;
+    }
+
+    let current = app
+        .selected_item
+        .as_ref()
+        .and_then(|item| app.visible_items.iter().position(|p| p == item));
+    let pressed = |key: Key| ctx.input_mut(|i| i.consume_key(Modifiers::NONE, key));
+
+    let manual_move = |key: Key| ctx.input_mut(|i| i.consume_key(Modifiers::ALT, key));
+    if app.tree_options.sort_mode == SortMode::Manual {
+        if let Some(path) = current.map(|index| app.visible_items[index].clone()) {
+            if manual_move(Key::ArrowUp) {
+                move_in_order(app, &path, -1);
+                app.scroll_to_selected = true;
+            } else if manual_move(Key::ArrowDown) {
+                move_in_order(app, &path, 1);
+                app.scroll_to_selected = true;
+            }
+        }
+    }
+
+    if pressed(Key::ArrowDown) {
+        let next = current.map_or(0, |index| index + 1);
+        if let Some(path) = app.visible_items.get(next).cloned() {
+            move_selection_to(app, path);
+        }
+    } else if pressed(Key::ArrowUp) {
+        let previous = current.map_or(0, |index| index.saturating_sub(1));
+        if let Some(path) = app.visible_items.get(previous).cloned() {
+            move_selection_to(app, path);
+        }
+    } else if let Some(path) = current.map(|index| app.visible_items[index].clone()) {
+        if pressed(Key::ArrowRight) {
+            if path.is_dir() && !app.expanded_dirs.contains(&path) {
+                toggle_expanded(app, &path);
+            } else if let Some(child) = app.visible_items.get(current.unwrap() + 1).cloned() {
+                if child.parent() == Some(path.as_path()) {
+                    move_selection_to(app, child);
+                }
+            }
+        } else if pressed(Key::ArrowLeft) {
+            if app.expanded_dirs.contains(&path) {
+                toggle_expanded(app, &path);
+            } else if let Some(parent) = path.parent() {
+                if app.visible_items.iter().any(|p| p == parent) {
+                    move_selection_to(app, parent.to_path_buf());
+                }
+            }
+        } else if pressed(Key::Enter) {
+            if path.is_dir() {
+                toggle_expanded(app, &path);
+            } else {
+                app.open_file(&path);
+                app.tree_has_focus = false;
+                app.focus_editor = true;
+            }
+        } else if pressed(Key::F2) {
+            start_rename(app, &path);
+        } else if pressed(Key::Delete) {
+            let selected_items = app.selected_items.clone();
+            app.delete_items(&selected_items);
         }
-        if let Some(root_dir) = &app.folder_path {
-            app.file_list = get_txt_files_and_dirs_in_directory(root_dir.clone());
+    }
+
+    type_ahead(ctx, app, current);
+}
+
+// 入力した文字で始まる項目へ移動（1秒入力がなければリセット）
+fn type_ahead(ctx: &Context, app: &mut TxtEditorApp, current: Option<usize>) {
+    let typed: String = ctx.input(|i| {
+        i.events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    });
+    if typed.is_empty() {
+        return;
+    }
+
+    let now = ctx.input(|i| i.time);
+    if now - app.type_ahead_time > 1.0 {
+        app.type_ahead.clear();
+    }
+    app.type_ahead_time = now;
+    app.type_ahead.push_str(&typed.to_lowercase());
+
+    // 新しく入力し始めたときは次の項目から探す
+    let start = match current {
+        Some(index) if app.type_ahead.chars().count() == 1 => index + 1,
+        Some(index) => index,
+        None => 0,
+    };
+    let count = app.visible_items.len();
+    let found = (0..count)
+        .map(|offset| (start + offset) % count)
+        .find(|&index| {
+            app.visible_items[index].file_name().is_some_and(|name| {
+                name.to_string_lossy()
+                    .to_lowercase()
+                    .starts_with(&app.type_ahead)
+            })
+        });
+    if let Some(index) = found {
+        move_selection_to(app, app.visible_items[index].clone());
+    }
+}
+
+// 項目がフォルダならそのフォルダ、ファイルなら親フォルダ
+fn target_directory(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.to_path_buf()
+    } else {
+        path.parent().unwrap_or(path).to_path_buf()
+    }
+}
+
+// クリップボードの項目を貼り付け
+fn paste_into(app: &mut TxtEditorApp, target_dir: &Path) {
+    let Some(clipboard) = &app.tree_clipboard else {
+        return;
+    };
+    let (sources, mode) = (clipboard.paths.clone(), clipboard.mode);
+    if mode == ClipboardMode::Cut {
+        // 移動した項目は再度貼り付けできない
+        app.tree_clipboard = None;
+    }
+    for source in sources {
+        app.queue_transfer(source, target_dir.to_path_buf(), mode);
+    }
+}
+
+// 同じフォルダに複製
+fn duplicate_item(app: &mut TxtEditorApp, path: &Path) {
+    if let Some(parent_dir) = path.parent() {
+        app.queue_transfer(
+            path.to_path_buf(),
+            parent_dir.to_path_buf(),
+            ClipboardMode::Copy,
+        );
+    }
+}
+
+// ドラッグ＆ドロップで移動できるか
+fn can_move_into(source: &Path, target_dir: &Path) -> bool {
+    source.parent() != Some(target_dir) && !target_dir.starts_with(source)
+}
+
+// ドロップ先の表示とドロップ時の移動
+fn handle_drop_target(
+    ui: &egui::Ui,
+    response: &Response,
+    target_dir: &Path,
+    app: &mut TxtEditorApp,
+) {
+    if !response.contains_pointer() {
+        return;
+    }
+    app.drop_target_dir = Some(target_dir.to_path_buf());
+
+    let accepts_tree_item =
+        response
+            .dnd_hover_payload::<TreeDragPayload>()
+            .is_some_and(|payload| {
+                payload
+                    .0
+                    .iter()
+                    .any(|source| can_move_into(source, target_dir))
+            });
+    let accepts_os_files = ui.ctx().input(|i| !i.raw.hovered_files.is_empty());
+    if accepts_tree_item || accepts_os_files {
+        ui.painter().rect_stroke(
+            response.rect.expand(2.0),
+            2.0,
+            Stroke::new(1.5, ui.visuals().selection.stroke.color),
+        );
+    }
+
+    if let Some(payload) = response.dnd_release_payload::<TreeDragPayload>() {
+        for source in &payload.0 {
+            if can_move_into(source, target_dir) {
+                app.queue_transfer(source.clone(), target_dir.to_path_buf(), ClipboardMode::Cut);
+            }
         }
     }
 }
 
-// テキストファイルの追加
-fn add_text_file_to_selected_directory(app: &mut TxtEditorApp) {
+// OSからドロップされたファイルをカーソル下のフォルダへコピー
+fn import_dropped_files(ctx: &Context, app: &mut TxtEditorApp) {
+    let dropped_files: Vec<PathBuf> = ctx.input(|i| {
+        i.raw
+            .dropped_files
+            .iter()
+            .filter_map(|file| file.path.clone())
+            .collect()
+    });
+    let Some(target_dir) = app.drop_target_dir.clone().or(app.folder_path.clone()) else {
+        return;
+    };
+    for path in dropped_files {
+        app.queue_transfer(path, target_dir.clone(), ClipboardMode::Copy);
+    }
+}
+
+// 新しいファイルの追加
+fn add_new_file_to_selected_directory(app: &mut TxtEditorApp) {
     if let Some(selected_item) = &app.selected_item {
         let parent_dir = if selected_item.is_dir() {
             selected_item.clone()
@@ -133,7 +698,7 @@ fn add_text_file_to_selected_directory(app: &mut TxtEditorApp) {
                 .unwrap_or(selected_item)
                 .to_path_buf()
         };
-        add_text_file(app, &parent_dir);
+        add_new_file(app, &parent_dir);
     }
 }
 
@@ -154,15 +719,17 @@ fn add_folder_to_selected_directory(app: &mut TxtEditorApp) {
 }
 
 // 名前の変更
-fn rename_item(path: &PathBuf, new_name: &str) -> io::Result<()> {
-    let new_name_with_ext = if path.is_file() {
-        format!("{}.txt", new_name)
-    } else {
-        new_name.to_string()
+fn rename_item(path: &PathBuf, new_name: &str) -> io::Result<PathBuf> {
+    // ファイルは元の拡張子を保つ
+    let new_name_with_ext = match path.extension() {
+        Some(extension) if path.is_file() => {
+            format!("{}.{}", new_name, extension.to_string_lossy())
+        }
+        _ => new_name.to_string(),
     };
     let new_path = path.with_file_name(new_name_with_ext);
-    fs::rename(path, new_path)?;
-    Ok(())
+    fs::rename(path, &new_path)?;
+    Ok(new_path)
 }
 
 // メインの表示
@@ -170,6 +737,22 @@ pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
     SidePanel::left("side_panel").show(ctx, |ui| {
         if let Some(ref folder_path) = app.folder_path {
             ui.label(format!("Directory: {}", folder_path.display()));
+            if app.selected_items.len() > 1 {
+                ui.label(format!("{} items selected", app.selected_items.len()));
+            }
+            ui.horizontal(|ui| {
+                sort_options(ui, app);
+                if ui
+                    .add_enabled(
+                        app.selected_file.is_some(),
+                        egui::Button::new("Reveal Active File"),
+                    )
+                    .clicked()
+                {
+                    app.reveal_active_file();
+                }
+            });
+            filter_options(ui, app);
             ui.separator();
 
             if app.rename_popup {
@@ -180,14 +763,133 @@ pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
                 new_folder_popup(ui, ctx, app);
             }
 
-            let paths = app.file_list.clone();
-            for path in paths {
-                display_directory(ui, &path, app);
+            if app.batch_rename.is_some() {
+                crate::ui::batch_rename::display(ctx, app);
+            }
+
+            if app.manuscript_report.is_some() {
+                crate::ui::manuscript::display(ctx, app);
+            }
+
+            if app.aozora_export.is_some() {
+                crate::ui::aozora::display(ctx, app);
+            }
+
+            if app.dialect_conversion.is_some() {
+                crate::ui::dialect::display(ctx, app);
+            }
+
+            if app.tag_popup {
+                tag_popup(ctx, app);
+            }
+
+            if app.paste_conflict.is_some() {
+                paste_conflict_popup(ctx, app);
+            }
+
+            if app.file_transfer.is_some() {
+                file_transfer_window(ctx, app);
+            }
+
+            app.drop_target_dir = None;
+            app.rendered_items.clear();
+            let mut scroll_area = ScrollArea::vertical().auto_shrink([false, false]);
+            if let Some(offset) = app.restore_tree_scroll.take() {
+                scroll_area = scroll_area.vertical_scroll_offset(offset);
             }
+            let output = scroll_area.show(ui, |ui| {
+                // 余白へのドロップはルートフォルダへ
+                let root_dir = app.folder_path.clone().unwrap();
+                display_entries(ui, &root_dir, app);
+
+                let blank_response = ui.allocate_response(ui.available_size(), Sense::click());
+                if blank_response.clicked() {
+                    app.tree_has_focus = true;
+                }
+                handle_drop_target(ui, &blank_response, &root_dir, app);
+            });
+            app.tree_scroll_offset = output.state.offset.y;
+            // 描画し終えた一覧に入れ替える（描画中のクリックは前のフレームの一覧で範囲を決める）
+            std::mem::swap(&mut app.visible_items, &mut app.rendered_items);
+            import_dropped_files(ctx, app);
+            handle_tree_keyboard(ctx, app);
         }
     });
 }
 
+// 並び替えの設定
+fn sort_options(ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    let options = &mut app.tree_options;
+    let previous = (options.sort_mode, options.folders_first);
+
+    ui.horizontal(|ui| {
+        ComboBox::from_id_source("tree_sort_mode")
+            .selected_text(format!("Sort: {}", options.sort_mode.label()))
+            .show_ui(ui, |ui| {
+                for mode in SortMode::ALL {
+                    ui.selectable_value(&mut options.sort_mode, mode, mode.label());
+                }
+            });
+        ui.add_enabled(
+            options.sort_mode != SortMode::Manual,
+            egui::Checkbox::new(&mut options.folders_first, "Folders first"),
+        );
+    });
+
+    if previous != (options.sort_mode, options.folders_first) {
+        app.refresh_file_list();
+    }
+}
+
+// 絞り込み・隠しファイル・拡張子の設定
+fn filter_options(ui: &mut egui::Ui, app: &mut TxtEditorApp) {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut app.tree_options.filter)
+                    .hint_text("Filter")
+                    .desired_width(120.0),
+            )
+            .changed();
+        changed |= ui
+            .checkbox(&mut app.tree_options.show_hidden, "Hidden")
+            .changed();
+
+        let response = ui.menu_button("Types", |ui| {
+            if app.known_extensions.is_empty() {
+                ui.weak("No files");
+            }
+            for extension in app.known_extensions.clone() {
+                let mut visible = !app.tree_options.hidden_extensions.contains(&extension);
+                if ui
+                    .checkbox(&mut visible, format!(".{}", extension))
+                    .changed()
+                {
+                    if visible {
+                        app.tree_options.hidden_extensions.remove(&extension);
+                    } else {
+                        app.tree_options.hidden_extensions.insert(extension);
+                    }
+                    changed = true;
+                }
+            }
+        });
+        // メニューを開いたときに拡張子の一覧を集め直す
+        if response.response.clicked() {
+            if let Some(root_dir) = &app.folder_path {
+                app.known_extensions =
+                    collect_extensions(root_dir, &app.tree_options, &app.ignore_rules);
+            }
+        }
+    });
+
+    if changed {
+        app.refresh_file_list();
+    }
+}
+
 // 名前変更のポップアップ
 fn rename_popup(ui: &mut egui::Ui, ctx: &Context, app: &mut TxtEditorApp) {
     egui::Window::new("Rename").show(ctx, |ui| {
@@ -195,13 +897,13 @@ fn rename_popup(ui: &mut egui::Ui, ctx: &Context, app: &mut TxtEditorApp) {
         ui.text_edit_singleline(&mut app.new_name);
 
         if ui.button("Rename").clicked() {
-            if let Some(ref rename_target) = app.rename_target {
-                if let Err(err) = rename_item(rename_target, &app.new_name) {
-                    eprintln!("Failed to rename item: {}", err);
-                } else {
-                    if let Some(root_dir) = &app.folder_path {
-                        app.file_list = get_txt_files_and_dirs_in_directory(root_dir.clone());
+            if let Some(rename_target) = app.rename_target.clone() {
+                match rename_item(&rename_target, &app.new_name) {
+                    Ok(new_path) => {
+                        app.path_moved(&rename_target, &new_path);
+                        app.refresh_file_list();
                     }
+                    Err(err) => eprintln!("Failed to rename item: {}", err),
                 }
             }
             app.rename_popup = false;
@@ -223,9 +925,7 @@ fn new_folder_popup(ui: &mut egui::Ui, ctx: &Context, app: &mut TxtEditorApp) {
                 if let Err(err) = create_folder(parent_dir, &app.new_folder_name) {
                     eprintln!("Failed to create folder: {}", err);
                 } else {
-                    if let Some(root_dir) = &app.folder_path {
-                        app.file_list = get_txt_files_and_dirs_in_directory(root_dir.clone());
-                    }
+                    app.refresh_file_list();
                 }
             }
             app.new_folder_popup = false;
@@ -235,3 +935,81 @@ fn new_folder_popup(ui: &mut egui::Ui, ctx: &Context, app: &mut TxtEditorApp) {
         }
     });
 }
+
+// 貼り付け時の競合確認のポップアップ
+fn paste_conflict_popup(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(pending) = &app.paste_conflict else {
+        return;
+    };
+    let mut resolution = None;
+
+    egui::Window::new("Item Already Exists").show(ctx, |ui| {
+        ui.label(format!(
+            "\"{}\" already exists in the destination folder.",
+            pending.destination.file_name().unwrap().to_string_lossy()
+        ));
+        ui.horizontal(|ui| {
+            if ui.button("Skip").clicked() {
+                resolution = Some(ConflictResolution::Skip);
+            }
+            if ui.button("Overwrite").clicked() {
+                resolution = Some(ConflictResolution::Overwrite);
+            }
+            if ui.button("Keep Both").clicked() {
+                resolution = Some(ConflictResolution::KeepBoth);
+            }
+        });
+    });
+
+    if let Some(resolution) = resolution {
+        app.resolve_paste_conflict(resolution);
+    }
+}
+
+// コピー・移動の進捗表示
+fn file_transfer_window(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(transfer) = &app.file_transfer else {
+        return;
+    };
+
+    egui::Window::new("File Transfer")
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(&transfer.label);
+            let text = match transfer.total {
+                Some(total) => format!("{} / {} files", transfer.done, total),
+                None => "Counting files...".to_string(),
+            };
+            ui.add(egui::ProgressBar::new(transfer.progress()).text(text));
+        });
+}
+
+// タグ追加のポップアップ
+fn tag_popup(ctx: &Context, app: &mut TxtEditorApp) {
+    egui::Window::new("Add Tag").show(ctx, |ui| {
+        ui.label(format!(
+            "Enter tag for {} item(s):",
+            app.selected_items.len()
+        ));
+        ui.text_edit_singleline(&mut app.new_tag);
+        let tag = app.new_tag.trim().to_string();
+        let valid = is_valid_tag(&tag);
+        if !tag.is_empty() && !valid {
+            ui.colored_label(Color32::RED, "Tags cannot contain commas or tabs.");
+        }
+
+        if ui.add_enabled(valid, egui::Button::new("Add")).clicked() {
+            for item in app.selected_items.clone() {
+                app.tags.add(&item, &tag);
+            }
+            if let Err(err) = app.tags.save() {
+                eprintln!("Failed to save tags: {}", err);
+            }
+            app.tag_popup = false;
+        }
+        if ui.button("Cancel").clicked() {
+            app.tag_popup = false;
+        }
+    });
+}
@@ -0,0 +1,54 @@
+use crate::app::TxtEditorApp;
+use crate::text::genko::{COLUMNS, ROWS};
+use eframe::egui::{self, Context, Grid, ScrollArea};
+
+// 原稿用紙換算の一覧（ファイルごとと合計）
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(report) = &app.manuscript_report else {
+        return;
+    };
+    let mut open = true;
+
+    egui::Window::new("Manuscript Pages")
+        .open(&mut open)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.label(format!("{}×{} manuscript paper", COLUMNS, ROWS));
+            ui.separator();
+
+            ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                Grid::new("manuscript_report")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("File");
+                        ui.strong("Characters");
+                        ui.strong("Pages");
+                        ui.end_row();
+
+                        for entry in &report.entries {
+                            let name = app
+                                .folder_path
+                                .as_ref()
+                                .and_then(|root| entry.path.strip_prefix(root).ok())
+                                .unwrap_or(&entry.path);
+                            ui.label(name.display().to_string());
+                            ui.label(entry.chars.to_string());
+                            ui.label(entry.pages.to_string());
+                            ui.end_row();
+                        }
+                    });
+            });
+            ui.separator();
+            ui.label(format!(
+                "Total: {} file(s), {} characters, {} pages",
+                report.entries.len(),
+                report.total_chars(),
+                report.total_pages()
+            ));
+        });
+
+    if !open {
+        app.manuscript_report = None;
+    }
+}
@@ -1,8 +1,24 @@
+pub mod aozora;
+pub mod assist;
+pub mod batch_rename;
+pub mod bottom_panel;
 pub mod central_panel;
+pub mod convert;
+pub mod dialect;
+pub mod gutter;
+pub mod highlight;
 pub mod left_panel;
+pub mod manuscript;
+pub mod problems;
 pub mod right_panel;
+pub mod ruler;
+pub mod statistics;
 pub mod top_panel;
+pub mod unsaved;
 pub mod utils;
+pub mod variants;
+pub mod vertical_preview;
+pub mod whitespace;
 
 use crate::app::TxtEditorApp;
 use eframe::egui::Context;
@@ -22,3 +38,19 @@ pub fn display_central_panel(app: &mut TxtEditorApp, ctx: &Context) {
 pub fn display_right_panel(app: &mut TxtEditorApp, ctx: &Context) {
     right_panel::display(app, ctx);
 }
+
+pub fn display_bottom_panel(app: &mut TxtEditorApp, ctx: &Context) {
+    bottom_panel::display(app, ctx);
+}
+
+pub fn display_vertical_preview(app: &mut TxtEditorApp, ctx: &Context) {
+    vertical_preview::display(app, ctx);
+}
+
+pub fn display_problems(app: &mut TxtEditorApp, ctx: &Context) {
+    problems::display(app, ctx);
+}
+
+pub fn display_unsaved_prompt(app: &mut TxtEditorApp, ctx: &Context) {
+    unsaved::display(app, ctx);
+}
@@ -0,0 +1,86 @@
+use crate::app::TxtEditorApp;
+use crate::text::lint::Rule;
+use eframe::egui::{self, Context, DragValue, ScrollArea, TopBottomPanel};
+
+// 校正の指摘の一覧（クリックで該当箇所を選択する）
+pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
+    if app.lint_rules_window {
+        rules_window(ctx, app);
+    }
+
+    TopBottomPanel::bottom("problems")
+        .resizable(true)
+        .default_height(160.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(format!("Problems ({})", app.diagnostics.len()));
+                if ui.button("Rules...").clicked() {
+                    app.lint_rules_window = true;
+                }
+                if ui.button("Close").clicked() {
+                    app.show_problems = false;
+                }
+            });
+            ui.separator();
+
+            let mut selected = None;
+            let row_height = ui.text_style_height(&egui::TextStyle::Body);
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show_rows(ui, row_height, app.diagnostics.len(), |ui, rows| {
+                    for diagnostic in &app.diagnostics[rows] {
+                        let text = format!(
+                            "Ln {}, Col {}    {}    [{}]",
+                            diagnostic.line,
+                            diagnostic.column,
+                            diagnostic.message,
+                            diagnostic.rule.label()
+                        );
+                        if ui.selectable_label(false, text).clicked() {
+                            selected = Some(diagnostic.chars.clone());
+                        }
+                    }
+                });
+            if let Some(range) = selected {
+                app.pending_selection = Some(range);
+                app.focus_editor = true;
+            }
+        });
+}
+
+// 規則ごとの有効・無効（ワークスペースの設定に保存する）
+fn rules_window(ctx: &Context, app: &mut TxtEditorApp) {
+    let mut open = true;
+    let mut changed = false;
+    egui::Window::new("Proofreading Rules")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let config = &mut app.lint_config;
+            for rule in Rule::ALL {
+                let mut enabled = config.is_enabled(rule);
+                if ui.checkbox(&mut enabled, rule.label()).changed() {
+                    if enabled {
+                        config.disabled.remove(&rule);
+                    } else {
+                        config.disabled.insert(rule);
+                    }
+                    changed = true;
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Maximum sentence length");
+                changed |= ui
+                    .add(DragValue::new(&mut config.max_sentence_length).clamp_range(10..=1000))
+                    .changed();
+            });
+        });
+
+    if changed {
+        app.lint_revision = None;
+    }
+    if !open {
+        app.lint_rules_window = false;
+    }
+}
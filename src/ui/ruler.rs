@@ -0,0 +1,22 @@
+use eframe::egui::{FontId, Pos2, Rect, Stroke, Ui};
+
+// 全角一文字の幅（桁の計算の単位）
+pub fn full_width(ui: &Ui, font: &FontId) -> f32 {
+    ui.fonts(|fonts| fonts.glyph_width(font, '　'))
+}
+
+// 指定した桁（全角で数える）の位置に縦線を引く
+pub fn paint(ui: &Ui, columns: &[usize], galley_pos: Pos2, rect: Rect, full_width: f32) {
+    let painter = ui.painter();
+    let clip = ui.clip_rect();
+    let stroke = Stroke::new(1.0, ui.visuals().weak_text_color().gamma_multiply(0.4));
+    for &column in columns {
+        let x = galley_pos.x + column as f32 * full_width;
+        if x > rect.right() {
+            continue;
+        }
+        let top = rect.top().max(clip.top());
+        let bottom = rect.bottom().min(clip.bottom());
+        painter.vline(x.round() + 0.5, top..=bottom, stroke);
+    }
+}
@@ -0,0 +1,62 @@
+use crate::app::TxtEditorApp;
+use crate::text::stats::Counts;
+use eframe::egui::{self, Context, Grid};
+
+type Row = (&'static str, fn(&Counts) -> String);
+
+// 統計パネル（文書全体と選択範囲）
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let exclude_markup = app.exclude_markup;
+    let document = app.stats.total().counted(exclude_markup);
+    let selection =
+        (!app.selection_range.is_empty()).then(|| app.selection_counts.counted(exclude_markup));
+
+    egui::Window::new("Statistics")
+        .open(&mut app.stats_window)
+        .show(ctx, |ui| {
+            Grid::new("statistics_grid")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.strong("Document");
+                    if selection.is_some() {
+                        ui.strong("Selection");
+                    }
+                    ui.end_row();
+
+                    let rows: [Row; 11] = [
+                        ("Characters", |c| c.total_chars().to_string()),
+                        ("Without line breaks", |c| c.chars.to_string()),
+                        ("Without whitespace", |c| c.visible_chars().to_string()),
+                        ("Lines", |c| (c.line_breaks + 1).to_string()),
+                        ("Paragraphs", |c| c.paragraphs.to_string()),
+                        ("Kanji", |c| with_ratio(c, c.kanji)),
+                        ("Hiragana", |c| with_ratio(c, c.hiragana)),
+                        ("Katakana", |c| with_ratio(c, c.katakana)),
+                        ("ASCII", |c| with_ratio(c, c.ascii)),
+                        ("Manuscript lines", |c| c.manuscript_lines.to_string()),
+                        ("Manuscript pages (20×20)", |c| {
+                            c.manuscript_pages().to_string()
+                        }),
+                    ];
+                    for (label, value) in rows {
+                        ui.label(label);
+                        ui.label(value(&document));
+                        if let Some(selection) = &selection {
+                            ui.label(value(selection));
+                        }
+                        ui.end_row();
+                    }
+                });
+            ui.separator();
+            ui.checkbox(
+                &mut app.exclude_markup,
+                "Exclude ruby and emphasis markup from character counts",
+            );
+        });
+}
+
+fn with_ratio(counts: &Counts, count: usize) -> String {
+    format!("{} ({:.1}%)", count, counts.ratio(count))
+}
@@ -1,16 +1,14 @@
 use crate::app::TxtEditorApp;
-use crate::file_operations::{get_txt_files_and_dirs_in_directory, move_to_trash};
-use crate::ui::utils::add_text_file; // インポートパスを修正
-use eframe::egui::{self, Context, Key, Modifiers, TopBottomPanel}; // インポート
+use crate::file_types::FILE_TYPES;
+use crate::ui::utils::{add_new_file, create_file}; // インポートパスを修正
+use eframe::egui::{self, Color32, ComboBox, Context, Key, Modifiers, TopBottomPanel}; // インポート
 
 pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
     TopBottomPanel::top("top_panel").show(ctx, |ui| {
         ui.horizontal(|ui| {
             if ui.button("Select Folder").clicked() {
                 if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                    app.folder_path = Some(path.clone());
-                    app.selected_item = Some(path.clone());
-                    app.file_list = get_txt_files_and_dirs_in_directory(path);
+                    app.open_folder(path);
                 }
             }
 
@@ -19,7 +17,7 @@ pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
 
             // selected_item を事前にコピーする
             let selected_item = app.selected_item.clone();
-            if ui.button("Add Text File").clicked() || add_file_shortcut {
+            if ui.button("New File").clicked() || add_file_shortcut {
                 if let Some(selected_item) = selected_item {
                     let parent_dir = if selected_item.is_dir() {
                         selected_item
@@ -29,55 +27,108 @@ pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
                             .unwrap_or(&selected_item)
                             .to_path_buf()
                     };
-                    add_text_file(app, &parent_dir);
+                    add_new_file(app, &parent_dir);
                 }
             }
 
             if app.new_file_popup {
-                egui::Window::new("Rename New Text File").show(ctx, |ui| {
-                    ui.label("Enter new file name:");
-                    ui.text_edit_singleline(&mut app.new_file_name);
+                new_file_popup(ctx, app);
+            }
+
+            // Delete キーはツリーにフォーカスがあるときに left_panel 側で処理する
+            if !app.selected_items.is_empty() && ui.button("Delete").clicked() {
+                let selected_items = app.selected_items.clone();
+                app.delete_items(&selected_items);
+            }
+
+            ui.toggle_value(&mut app.vertical_preview, "Preview");
+            ui.toggle_value(&mut app.show_whitespace, "Whitespace");
+
+            if app.folder_path.is_some() && ui.button("Variants").clicked() {
+                crate::ui::variants::analyze_workspace(app);
+            }
+            if app.variant_analysis.is_some() {
+                crate::ui::variants::display(ctx, app);
+            }
+        });
+    });
+}
+
+// 新規ファイルのポップアップ（種類とテンプレートを選択）
+fn new_file_popup(ctx: &Context, app: &mut TxtEditorApp) {
+    egui::Window::new("New File").show(ctx, |ui| {
+        ui.label("Enter new file name:");
+        ui.text_edit_singleline(&mut app.new_file_name);
 
-                    if ui.button("Rename").clicked() {
-                        if let Some(ref new_file_path) = app.new_file_path {
-                            let parent_dir = new_file_path.parent().unwrap();
-                            let new_file_path_renamed =
-                                parent_dir.join(format!("{}.txt", app.new_file_name));
-                            std::fs::rename(new_file_path, &new_file_path_renamed)
-                                .expect("Failed to rename file");
-                            app.new_file_popup = false;
-                            app.new_file_path = Some(new_file_path_renamed);
-                            if let Some(root_dir) = &app.folder_path {
-                                app.file_list =
-                                    get_txt_files_and_dirs_in_directory(root_dir.clone());
-                            }
+        let file_type = &FILE_TYPES[app.new_file_type];
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+            ComboBox::from_id_source("new_file_type")
+                .selected_text(format!(
+                    "{} (.{})",
+                    file_type.name,
+                    file_type.default_extension()
+                ))
+                .show_ui(ui, |ui| {
+                    for (index, file_type) in FILE_TYPES.iter().enumerate() {
+                        let label =
+                            format!("{} (.{})", file_type.name, file_type.default_extension());
+                        if ui
+                            .selectable_value(&mut app.new_file_type, index, label)
+                            .clicked()
+                        {
+                            app.new_file_template = 0;
                         }
                     }
-                    if ui.button("Cancel").clicked() {
-                        app.new_file_popup = false;
+                });
+        });
+
+        let file_type = &FILE_TYPES[app.new_file_type];
+        ui.horizontal(|ui| {
+            ui.label("Template:");
+            ComboBox::from_id_source("new_file_template")
+                .selected_text(file_type.templates[app.new_file_template].name)
+                .show_ui(ui, |ui| {
+                    for (index, template) in file_type.templates.iter().enumerate() {
+                        ui.selectable_value(&mut app.new_file_template, index, template.name);
                     }
                 });
-            }
+        });
 
-            let delete_file_shortcut = ctx.input(|i| i.key_pressed(Key::Delete));
-            if let Some(ref selected_file) = app.selected_item {
-                if ui.button("Delete").clicked() || delete_file_shortcut {
-                    if let Err(err) = move_to_trash(selected_file) {
-                        eprintln!("Failed to move file to trash: {}", err);
-                    } else {
-                        app.file_list.retain(|f| f != selected_file);
-                        app.selected_item = None;
-                        app.file_contents.clear();
-                        if let Some(folder_path) = &app.folder_path {
-                            app.file_list =
-                                get_txt_files_and_dirs_in_directory(folder_path.clone());
+        let exists = app.new_file_parent.as_ref().is_some_and(|parent_dir| {
+            parent_dir
+                .join(format!(
+                    "{}.{}",
+                    app.new_file_name,
+                    file_type.default_extension()
+                ))
+                .exists()
+        });
+        if exists {
+            ui.colored_label(Color32::RED, "A file with this name already exists");
+        }
+
+        ui.horizontal(|ui| {
+            let can_create = !exists && !app.new_file_name.trim().is_empty();
+            if ui
+                .add_enabled(can_create, egui::Button::new("Create"))
+                .clicked()
+            {
+                if let Some(parent_dir) = app.new_file_parent.clone() {
+                    let template = &file_type.templates[app.new_file_template];
+                    match create_file(&parent_dir, &app.new_file_name, file_type, template) {
+                        Ok(path) => {
+                            app.refresh_file_list();
+                            app.open_file(&path);
                         }
+                        Err(err) => eprintln!("Failed to create file: {}", err),
                     }
                 }
+                app.new_file_popup = false;
+            }
+            if ui.button("Cancel").clicked() {
+                app.new_file_popup = false;
             }
-
-            let char_count = app.file_contents.chars().count();
-            ui.label(format!("Character count: {}", char_count));
         });
     });
 }
@@ -0,0 +1,65 @@
+use crate::app::TxtEditorApp;
+use crate::document::TextEncoding;
+use eframe::egui::{self, Align2, Context};
+
+// 保存できなかった変更があるまま別の文書を開こうとしたときの確認
+pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
+    let mut choice = None;
+    egui::Window::new("Unsaved Changes")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            let name = app
+                .selected_file
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            ui.label(format!("Changes to {} could not be saved.", name));
+            if let Some(err) = &app.save_error {
+                ui.label(err);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Save as UTF-8").clicked() {
+                    choice = Some(Choice::SaveUtf8);
+                }
+                if ui.button("Discard Changes").clicked() {
+                    choice = Some(Choice::Discard);
+                }
+                if ui.button("Cancel").clicked() {
+                    choice = Some(Choice::Cancel);
+                }
+            });
+        });
+
+    let Some(choice) = choice else {
+        return;
+    };
+    let Some(action) = app.unsaved_prompt.take() else {
+        return;
+    };
+    match choice {
+        Choice::SaveUtf8 => {
+            app.encoding = TextEncoding::Utf8;
+            if app.save_file_if_modified() {
+                app.resume_pending_open(action);
+            } else {
+                app.unsaved_prompt = Some(action);
+            }
+        }
+        Choice::Discard => {
+            app.file_modified = false;
+            app.save_error = None;
+            app.resume_pending_open(action);
+        }
+        Choice::Cancel => {}
+    }
+}
+
+enum Choice {
+    SaveUtf8,
+    Discard,
+    Cancel,
+}
@@ -1,20 +1,35 @@
 use crate::app::TxtEditorApp;
-use crate::file_operations::get_txt_files_and_dirs_in_directory;
+use crate::file_types::{FileType, Template};
+use eframe::egui::{Grid, RichText, Ui};
 use std::fs;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 
-pub fn add_text_file(app: &mut TxtEditorApp, parent_dir: &Path) {
-    let new_file_name = "new_file";
-    let new_file_path = parent_dir.join(format!("{}.txt", new_file_name));
-    std::fs::File::create(&new_file_path).expect("Failed to create file");
+// 新規ファイルのポップアップを開く（作成は種類とテンプレートを選んだ後）
+pub fn add_new_file(app: &mut TxtEditorApp, parent_dir: &Path) {
     app.new_file_popup = true;
-    app.new_file_path = Some(new_file_path);
-    app.new_file_name = new_file_name.to_string();
-    if let Some(root_dir) = &app.folder_path {
-        app.file_list = get_txt_files_and_dirs_in_directory(root_dir.clone());
+    app.new_file_parent = Some(parent_dir.to_path_buf());
+    app.new_file_name = "new_file".to_string();
+    app.new_file_template = 0;
+}
+
+// 種類に応じた拡張子でファイルを作成し、テンプレートの内容を書き込む
+pub fn create_file(
+    parent_dir: &Path,
+    name: &str,
+    file_type: &FileType,
+    template: &Template,
+) -> io::Result<PathBuf> {
+    let new_file_path = parent_dir.join(format!("{}.{}", name, file_type.default_extension()));
+    if new_file_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", new_file_path.display()),
+        ));
     }
+    fs::write(&new_file_path, template.contents)?;
+    Ok(new_file_path)
 }
 
 pub fn create_folder(parent_dir: &PathBuf, folder_name: &str) -> io::Result<()> {
@@ -22,3 +37,28 @@ pub fn create_folder(parent_dir: &PathBuf, folder_name: &str) -> io::Result<()>
     fs::create_dir(new_folder_path)?;
     Ok(())
 }
+
+// 行を増減しない書き換えで変わる行（行番号、変更前、変更後）
+pub fn changed_lines(before: &str, after: &str, first_line: usize) -> Vec<(usize, String, String)> {
+    before
+        .split('\n')
+        .zip(after.split('\n'))
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(index, (before, after))| (first_line + index, before.to_string(), after.to_string()))
+        .collect()
+}
+
+// 変わる行の一覧（変更前は取り消し線で表示する）
+pub fn changes_grid(ui: &mut Ui, id: impl std::hash::Hash, changes: &[(usize, String, String)]) {
+    Grid::new(id).num_columns(2).striped(true).show(ui, |ui| {
+        for (line, before, after) in changes {
+            ui.label(line.to_string());
+            ui.vertical(|ui| {
+                ui.label(RichText::new(before).strikethrough().weak());
+                ui.label(after);
+            });
+            ui.end_row();
+        }
+    });
+}
@@ -0,0 +1,305 @@
+use crate::app::TxtEditorApp;
+use crate::document;
+use crate::text::genko::collect_text_files;
+use crate::text::variants::{analyze, unify, Dictionary, VariantReport};
+use crate::ui::utils::{changed_lines, changes_grid};
+use eframe::egui::{self, CollapsingHeader, Context, ScrollArea};
+use std::path::{Path, PathBuf};
+
+// ワークスペース全体の表記ゆれの一覧
+pub struct VariantAnalysis {
+    pub dictionary: Dictionary,
+    pub reports: Vec<VariantReport>,
+    // 組ごとに統一する表記の番号
+    pub targets: Vec<usize>,
+    pub message: Option<String>,
+    pub plan: Option<UnifyPlan>,
+}
+
+// 統一の確認（ファイルごとに変わる行を見て、書き換えるファイルを選ぶ）
+pub struct UnifyPlan {
+    pub report: usize,
+    pub target: String,
+    pub files: Vec<UnifyFile>,
+}
+
+pub struct UnifyFile {
+    pub path: PathBuf,
+    pub replaced: usize,
+    pub changes: Vec<(usize, String, String)>,
+    pub selected: bool,
+}
+
+impl VariantAnalysis {
+    pub fn new(root: &Path) -> Self {
+        let dictionary = Dictionary::load(root);
+        let mut files = Vec::new();
+        collect_text_files(root, &mut files);
+        let reports = analyze(&files, &dictionary);
+        // 既定は推奨する表記（先頭）
+        let targets = vec![0; reports.len()];
+        Self {
+            dictionary,
+            reports,
+            targets,
+            message: None,
+            plan: None,
+        }
+    }
+}
+
+pub fn analyze_workspace(app: &mut TxtEditorApp) {
+    let Some(root) = app.folder_path.clone() else {
+        return;
+    };
+    app.save_file_if_modified();
+    app.variant_analysis = Some(VariantAnalysis::new(&root));
+}
+
+pub fn display(ctx: &Context, app: &mut TxtEditorApp) {
+    let Some(analysis) = &mut app.variant_analysis else {
+        return;
+    };
+    let mut open = true;
+    let mut rescan = false;
+    let mut edit_dictionary = false;
+    let mut unify_group = None;
+    let mut apply_plan = false;
+    let mut cancel_plan = false;
+    let mut open_path: Option<PathBuf> = None;
+    let root = app.folder_path.clone().unwrap_or_default();
+
+    egui::Window::new("Orthographic Variants")
+        .open(&mut open)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Rescan").clicked() {
+                    rescan = true;
+                }
+                if ui.button("Edit Dictionary").clicked() {
+                    edit_dictionary = true;
+                }
+            });
+            if let Some(message) = &analysis.message {
+                ui.label(message);
+            }
+            ui.separator();
+
+            if let Some(plan) = &mut analysis.plan {
+                match plan_confirmation(ui, plan, &root) {
+                    Some(true) => apply_plan = true,
+                    Some(false) => cancel_plan = true,
+                    None => {}
+                }
+                return;
+            }
+
+            if analysis.reports.is_empty() {
+                ui.label("No variant spellings found.");
+                return;
+            }
+            ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                for (index, report) in analysis.reports.iter().enumerate() {
+                    let group = &analysis.dictionary.groups[report.group];
+                    ui.horizontal(|ui| {
+                        for (form_index, form) in group.forms.iter().enumerate() {
+                            let label = format!("{} ({})", form, report.counts[form_index]);
+                            ui.radio_value(&mut analysis.targets[index], form_index, label);
+                        }
+                        let target = &group.forms[analysis.targets[index]];
+                        if ui.button(format!("Unify to {}", target)).clicked() {
+                            unify_group = Some(index);
+                        }
+                    });
+
+                    CollapsingHeader::new(format!("{} file(s)", report.files.len()))
+                        .id_source(("variant_files", report.group))
+                        .show(ui, |ui| {
+                            for (path, counts) in &report.files {
+                                let name = path.strip_prefix(&root).unwrap_or(path);
+                                let summary: Vec<String> = group
+                                    .forms
+                                    .iter()
+                                    .zip(counts)
+                                    .filter(|(_, &count)| count > 0)
+                                    .map(|(form, count)| format!("{} {}", form, count))
+                                    .collect();
+                                let text = format!("{}  ({})", name.display(), summary.join(", "));
+                                if ui.link(text).clicked() {
+                                    open_path = Some(path.clone());
+                                }
+                            }
+                        });
+                    ui.separator();
+                }
+            });
+        });
+
+    if let Some(index) = unify_group {
+        plan_unify(app, index);
+    } else if apply_plan {
+        unify_variants(ctx, app);
+    } else if cancel_plan {
+        if let Some(analysis) = &mut app.variant_analysis {
+            analysis.plan = None;
+        }
+    } else if rescan {
+        analyze_workspace(app);
+    }
+    if edit_dictionary {
+        match Dictionary::ensure_file(&root) {
+            Ok(path) => open_path = Some(path),
+            Err(err) => eprintln!("Failed to create variant dictionary: {}", err),
+        }
+    }
+    if let Some(path) = open_path {
+        app.open_file(&path);
+    }
+    if !open {
+        app.variant_analysis = None;
+    }
+}
+
+// 書き換えるファイルの確認（適用なら Some(true)、取りやめなら Some(false)）
+fn plan_confirmation(ui: &mut egui::Ui, plan: &mut UnifyPlan, root: &Path) -> Option<bool> {
+    let mut choice = None;
+    ui.label(format!(
+        "Unify to {}: choose the files to change",
+        plan.target
+    ));
+    ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+        for file in &mut plan.files {
+            let name = file.path.strip_prefix(root).unwrap_or(&file.path);
+            ui.checkbox(
+                &mut file.selected,
+                format!("{}  ({} change(s))", name.display(), file.replaced),
+            );
+            CollapsingHeader::new(format!("{} line(s)", file.changes.len()))
+                .id_source(("variant_changes", &file.path))
+                .show(ui, |ui| {
+                    changes_grid(ui, ("variant_grid", &file.path), &file.changes)
+                });
+        }
+    });
+    ui.separator();
+    let selected = plan.files.iter().filter(|file| file.selected).count();
+    ui.horizontal(|ui| {
+        let apply = egui::Button::new(format!("Apply to {} file(s)", selected));
+        if ui.add_enabled(selected > 0, apply).clicked() {
+            choice = Some(true);
+        }
+        if ui.button("Cancel").clicked() {
+            choice = Some(false);
+        }
+    });
+    choice
+}
+
+// 開いているファイルはエディタの内容を、ほかのファイルは保存されている内容を使う
+fn current_text(app: &TxtEditorApp, path: &Path) -> Result<String, String> {
+    if app.selected_file.as_deref() == Some(path) {
+        return Ok(app.file_contents.clone());
+    }
+    document::load(path)
+        .map(|loaded| loaded.text)
+        .map_err(|err| err.to_string())
+}
+
+// 選んだ表記に置き換えたときに変わる行をファイルごとに集める
+fn plan_unify(app: &mut TxtEditorApp, index: usize) {
+    let Some(analysis) = &app.variant_analysis else {
+        return;
+    };
+    let report = &analysis.reports[index];
+    let group = &analysis.dictionary.groups[report.group];
+    let target = group.forms[analysis.targets[index]].clone();
+
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for (path, _) in &report.files {
+        match current_text(app, path) {
+            Ok(text) => {
+                let (unified, replaced) = unify(&text, group, &target);
+                if replaced > 0 {
+                    files.push(UnifyFile {
+                        path: path.clone(),
+                        replaced,
+                        changes: changed_lines(&text, &unified, 1),
+                        selected: true,
+                    });
+                }
+            }
+            Err(err) => errors.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+
+    let analysis = app.variant_analysis.as_mut().unwrap();
+    analysis.message = (!errors.is_empty()).then(|| errors.join("\n"));
+    analysis.plan = Some(UnifyPlan {
+        report: index,
+        target,
+        files,
+    });
+}
+
+// 選んだファイルの表記をそろえ、元の文字コードと改行コードで保存する
+// 開いているファイルはエディタの中で置き換えて、取り消せるようにする
+fn unify_variants(ctx: &Context, app: &mut TxtEditorApp) {
+    let saved = app.save_file_if_modified();
+    let Some(analysis) = &mut app.variant_analysis else {
+        return;
+    };
+    // 開いているファイルの変更を保存できないうちは書き換えない
+    if !saved {
+        analysis.message = Some(format!(
+            "Save the open file first: {}",
+            app.save_error.as_deref().unwrap_or_default()
+        ));
+        return;
+    }
+    let Some(plan) = analysis.plan.take() else {
+        return;
+    };
+    let group = &analysis.dictionary.groups[analysis.reports[plan.report].group];
+
+    let mut total = 0;
+    let mut errors = Vec::new();
+    let mut open_file_text = None;
+    for file in plan.files.iter().filter(|file| file.selected) {
+        if app.selected_file.as_ref() == Some(&file.path) {
+            let (text, replaced) = unify(&app.file_contents, group, &plan.target);
+            total += replaced;
+            open_file_text = Some(text);
+            continue;
+        }
+        let result = document::load(&file.path)
+            .map_err(|err| err.to_string())
+            .and_then(|loaded| {
+                let (text, replaced) = unify(&loaded.text, group, &plan.target);
+                if replaced > 0 {
+                    document::save(&file.path, &text, loaded.encoding, loaded.line_ending)?;
+                }
+                Ok(replaced)
+            });
+        match result {
+            Ok(replaced) => total += replaced,
+            Err(err) => errors.push(format!("{}: {}", file.path.display(), err)),
+        }
+    }
+
+    if let Some(text) = open_file_text {
+        let end = app.file_contents.chars().count();
+        app.replace_range(ctx, 0..end, &text);
+    }
+
+    analyze_workspace(app);
+    if let Some(analysis) = &mut app.variant_analysis {
+        let mut message = format!("Replaced {} occurrence(s) with {}", total, plan.target);
+        for error in errors {
+            message.push('\n');
+            message.push_str(&error);
+        }
+        analysis.message = Some(message);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::app::TxtEditorApp;
+use crate::text::vertical::{layout, Cell, Column, GlyphKind};
+use eframe::egui::{
+    self, Align2, Color32, Context, FontId, Painter, Pos2, Rect, ScrollArea, Sense, SidePanel,
+    Stroke, Vec2,
+};
+use std::f32::consts::FRAC_PI_2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PreviewMode {
+    Vertical,
+    Horizontal,
+}
+
+// 縦書き・横書きのプレビュー（ルビを表示する）
+pub fn display(app: &mut TxtEditorApp, ctx: &Context) {
+    SidePanel::right("vertical_preview")
+        .resizable(true)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Preview");
+                ui.selectable_value(&mut app.preview_mode, PreviewMode::Vertical, "Vertical");
+                ui.selectable_value(&mut app.preview_mode, PreviewMode::Horizontal, "Horizontal");
+            });
+            if app.selected_file.is_none() {
+                return;
+            }
+
+            let font_size = app.font_size.max(16.0);
+            let cell = font_size * 1.2;
+            // 列（行）の間隔（本文とルビ、余白）
+            let pitch = cell * 1.8;
+            let available = ui.available_size();
+            let length = match app.preview_mode {
+                PreviewMode::Vertical => available.y - cell,
+                PreviewMode::Horizontal => available.x - cell,
+            };
+            let cells_per_column = (length / cell).floor().max(2.0) as usize;
+
+            // 文書か大きさが変わったときだけ組み直す
+            let key = (app.text_revision, cells_per_column, app.preview_mode);
+            if app.vertical_layout_key != Some(key) {
+                app.vertical_columns = layout(&app.file_contents, cells_per_column);
+                app.vertical_layout_key = Some(key);
+            }
+
+            let clicked = match app.preview_mode {
+                PreviewMode::Vertical => vertical(ui, app, cells_per_column, cell, pitch),
+                PreviewMode::Horizontal => horizontal(ui, app, cells_per_column, cell, pitch),
+            };
+            if let Some(char_index) = clicked {
+                app.pending_selection = Some(char_index..char_index);
+                app.focus_editor = true;
+            }
+        });
+}
+
+// 右から左へ列を並べる
+fn vertical(
+    ui: &mut egui::Ui,
+    app: &TxtEditorApp,
+    rows: usize,
+    cell: f32,
+    pitch: f32,
+) -> Option<usize> {
+    let columns = &app.vertical_columns;
+    let total_width = columns.len() as f32 * pitch;
+    let mut clicked = None;
+
+    ScrollArea::horizontal()
+        .stick_to_right(true)
+        .auto_shrink([false, false])
+        .show_viewport(ui, |ui, viewport| {
+            let size = Vec2::new(total_width, (rows + 1) as f32 * cell);
+            let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+            let painter = ui.painter_at(rect);
+
+            // 最初の列が右端
+            let first = ((total_width - viewport.right()) / pitch).floor().max(0.0) as usize;
+            let last = ((total_width - viewport.left()) / pitch).ceil() as usize;
+            for (index, column) in columns.iter().enumerate().take(last).skip(first) {
+                let center_x = rect.right() - (index as f32 + 0.5) * pitch;
+                paint_cells(&painter, app, column, |row| {
+                    Pos2::new(center_x, rect.top() + (row as f32 + 0.5) * cell)
+                });
+                paint_vertical_ruby(&painter, app, column, center_x, rect.top(), cell);
+            }
+
+            // クリックした位置にエディタのカーソルを移動する
+            if let Some(pos) = response
+                .interact_pointer_pos()
+                .filter(|_| response.clicked())
+            {
+                let index = ((rect.right() - pos.x) / pitch).floor() as usize;
+                let row = ((pos.y - rect.top()) / cell).floor() as usize;
+                clicked = columns.get(index).map(|column| char_index_at(column, row));
+            }
+        });
+    clicked
+}
+
+// 上から下へ行を並べ、ルビを親文字の上に表示する
+fn horizontal(
+    ui: &mut egui::Ui,
+    app: &TxtEditorApp,
+    columns_per_row: usize,
+    cell: f32,
+    pitch: f32,
+) -> Option<usize> {
+    let rows = &app.vertical_columns;
+    let mut clicked = None;
+
+    ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show_viewport(ui, |ui, viewport| {
+            let size = Vec2::new(
+                (columns_per_row + 1) as f32 * cell,
+                rows.len() as f32 * pitch,
+            );
+            let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+            let painter = ui.painter_at(rect);
+
+            let first = (viewport.top() / pitch).floor().max(0.0) as usize;
+            let last = (viewport.bottom() / pitch).ceil() as usize;
+            for (index, row) in rows.iter().enumerate().take(last).skip(first) {
+                let ruby_y = rect.top() + index as f32 * pitch + cell * 0.3;
+                let center_y = ruby_y + cell * 0.8;
+                paint_cells(&painter, app, row, |column| {
+                    Pos2::new(rect.left() + (column as f32 + 0.5) * cell, center_y)
+                });
+
+                let ruby_font = FontId::proportional(cell * 0.42);
+                let color = ui.visuals().text_color();
+                for ruby in &row.rubies {
+                    let cells = ruby.cells.min(row.cells.len() - ruby.first_cell).max(1);
+                    let center_x =
+                        rect.left() + (ruby.first_cell as f32 + cells as f32 / 2.0) * cell;
+                    painter.text(
+                        Pos2::new(center_x, ruby_y),
+                        Align2::CENTER_CENTER,
+                        &ruby.text,
+                        ruby_font.clone(),
+                        color,
+                    );
+                }
+            }
+
+            if let Some(pos) = response
+                .interact_pointer_pos()
+                .filter(|_| response.clicked())
+            {
+                let index = ((pos.y - rect.top()) / pitch).floor() as usize;
+                let column = ((pos.x - rect.left()) / cell).floor() as usize;
+                clicked = rows.get(index).map(|row| char_index_at(row, column));
+            }
+        });
+    clicked
+}
+
+fn char_index_at(column: &Column, position: usize) -> usize {
+    column
+        .cells
+        .get(position)
+        .map_or(column.char_index, |cell| cell.char_index)
+}
+
+// 各マスの文字と選択範囲・カーソルを描く（center はマスの番号から中心を返す）
+fn paint_cells(
+    painter: &Painter,
+    app: &TxtEditorApp,
+    column: &Column,
+    center: impl Fn(usize) -> Pos2,
+) {
+    let visuals = painter.ctx().style().visuals.clone();
+    let color = visuals.text_color();
+    let font_size = app.font_size.max(16.0);
+    let cell = font_size * 1.2;
+    let vertical = app.preview_mode == PreviewMode::Vertical;
+    let selection = &app.selection_range;
+
+    for (position, glyph) in column.cells.iter().enumerate() {
+        let center = center(position);
+        let cell_rect = Rect::from_center_size(center, Vec2::splat(cell));
+        let glyph_chars = glyph.text.chars().count();
+
+        if glyph.char_index < selection.end && selection.start < glyph.char_index + glyph_chars {
+            painter.rect_filled(cell_rect, 0.0, visuals.selection.bg_fill);
+        } else if selection.is_empty() && glyph.char_index == selection.start {
+            // カーソルの位置（縦書きはマスの上端、横書きは左端）
+            let caret = if vertical {
+                [cell_rect.left_top(), cell_rect.right_top()]
+            } else {
+                [cell_rect.left_top(), cell_rect.left_bottom()]
+            };
+            painter.line_segment(caret, Stroke::new(2.0, color));
+        }
+
+        paint_glyph(painter, glyph, center, cell, font_size, vertical, color);
+
+        // 傍点は縦書きでは右、横書きでは上に付ける
+        if glyph.emphasis {
+            let offset = if vertical {
+                Vec2::new(cell * 0.55, 0.0)
+            } else {
+                Vec2::new(0.0, -cell * 0.55)
+            };
+            painter.circle_filled(center + offset, cell * 0.07, color);
+        }
+    }
+}
+
+fn paint_glyph(
+    painter: &Painter,
+    glyph: &Cell,
+    center: Pos2,
+    cell: f32,
+    font_size: f32,
+    vertical: bool,
+    color: Color32,
+) {
+    let font = FontId::proportional(font_size);
+    let glyph_chars = glyph.text.chars().count();
+    let kind = if vertical {
+        glyph.kind
+    } else {
+        GlyphKind::Upright
+    };
+
+    match kind {
+        GlyphKind::Upright if glyph_chars > 1 => {
+            // 縦中横の数字は横書きではそのまま縮めて並べる
+            painter.text(
+                center,
+                Align2::CENTER_CENTER,
+                &glyph.text,
+                FontId::proportional(font_size * 0.6),
+                color,
+            );
+        }
+        GlyphKind::Upright => {
+            painter.text(center, Align2::CENTER_CENTER, &glyph.text, font, color);
+        }
+        GlyphKind::Punctuation | GlyphKind::Small => {
+            let offset = if kind == GlyphKind::Punctuation {
+                Vec2::new(cell * 0.3, -cell * 0.3)
+            } else {
+                Vec2::new(cell * 0.08, -cell * 0.08)
+            };
+            painter.text(
+                center + offset,
+                Align2::CENTER_CENTER,
+                &glyph.text,
+                font,
+                color,
+            );
+        }
+        GlyphKind::Horizontal => {
+            // マスに収まるように縮める
+            let size = font_size * (1.0 / glyph_chars.max(1) as f32).max(0.6) * 1.1;
+            painter.text(
+                center,
+                Align2::CENTER_CENTER,
+                &glyph.text,
+                FontId::proportional(size.min(font_size)),
+                color,
+            );
+        }
+        GlyphKind::Rotated => {
+            // 時計回りに 90 度回転させ、回転後の中心をマスの中心に合わせる
+            let galley = painter.layout_no_wrap(glyph.text.clone(), font, color);
+            let size = galley.size();
+            let pos = Pos2::new(center.x + size.y / 2.0, center.y - size.x / 2.0);
+            painter.add(egui::epaint::TextShape::new(pos, galley, color).with_angle(FRAC_PI_2));
+        }
+    }
+}
+
+// ルビは親文字の右側に小さく並べる
+fn paint_vertical_ruby(
+    painter: &Painter,
+    app: &TxtEditorApp,
+    column: &Column,
+    center_x: f32,
+    top: f32,
+    cell: f32,
+) {
+    let font_size = app.font_size.max(16.0);
+    let color = painter.ctx().style().visuals.text_color();
+    let ruby_font = FontId::proportional(font_size * 0.5);
+    for ruby in &column.rubies {
+        let cells = ruby.cells.min(column.cells.len() - ruby.first_cell).max(1);
+        let base_top = top + ruby.first_cell as f32 * cell;
+        let base_height = cells as f32 * cell;
+        let ruby_chars: Vec<char> = ruby.text.chars().collect();
+        let step = (base_height / ruby_chars.len() as f32).min(font_size * 0.55);
+        let start = base_top + (base_height - step * ruby_chars.len() as f32) / 2.0;
+        for (i, c) in ruby_chars.iter().enumerate() {
+            painter.text(
+                Pos2::new(center_x + cell * 0.75, start + (i as f32 + 0.5) * step),
+                Align2::CENTER_CENTER,
+                c,
+                ruby_font.clone(),
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::text::indent::INDENT;
+use eframe::egui::{Color32, Galley, Painter, Pos2, Rect, Stroke, Ui, Vec2};
+
+// 空白と行末を記号で表示する（半角スペースは点、全角スペースは四角、タブは矢印、改行は鉤）
+pub fn paint(ui: &Ui, galley: &Galley, galley_pos: Pos2) {
+    let painter = ui.painter();
+    let clip = ui.clip_rect();
+    let color = ui.visuals().weak_text_color().gamma_multiply(0.6);
+    let stroke = Stroke::new(1.0, color);
+
+    for row in &galley.rows {
+        let row_rect = row.rect.translate(galley_pos.to_vec2());
+        if row_rect.bottom() < clip.top() || row_rect.top() > clip.bottom() {
+            continue;
+        }
+        for glyph in &row.glyphs {
+            let rect = glyph.logical_rect().translate(galley_pos.to_vec2());
+            match glyph.chr {
+                ' ' => {
+                    painter.circle_filled(rect.center(), 1.2, color);
+                }
+                INDENT => {
+                    let size = rect.height().min(rect.width()) * 0.6;
+                    let square = Rect::from_center_size(rect.center(), Vec2::splat(size));
+                    painter.rect_stroke(square, 1.0, stroke);
+                }
+                '\t' => {
+                    let y = rect.center().y;
+                    let left = Pos2::new(rect.left() + 2.0, y);
+                    let right = Pos2::new(rect.right() - 2.0, y);
+                    painter.line_segment([left, right], stroke);
+                    painter.line_segment([right, right + Vec2::new(-3.0, -3.0)], stroke);
+                    painter.line_segment([right, right + Vec2::new(-3.0, 3.0)], stroke);
+                }
+                _ => {}
+            }
+        }
+        if row.ends_with_newline {
+            paint_line_end(painter, row_rect, color);
+        }
+    }
+}
+
+fn paint_line_end(painter: &Painter, row: Rect, color: Color32) {
+    let stroke = Stroke::new(1.0, color);
+    let size = row.height() * 0.3;
+    let x = row.right() + 3.0 + size;
+    let y = row.center().y;
+    let corner = Pos2::new(x, y + size * 0.5);
+    painter.line_segment([Pos2::new(x, y - size * 0.5), corner], stroke);
+    painter.line_segment([corner, Pos2::new(x - size, y + size * 0.5)], stroke);
+    painter.line_segment(
+        [
+            Pos2::new(x - size, y + size * 0.5),
+            Pos2::new(x - size * 0.6, y + size * 0.15),
+        ],
+        stroke,
+    );
+}
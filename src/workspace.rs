@@ -0,0 +1,117 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// ワークスペースごとの設定を保存するフォルダ
+pub fn metadata_dir(root: &Path) -> PathBuf {
+    root.join(".txt-editor")
+}
+
+pub fn read_metadata(root: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(metadata_dir(root).join(name)).ok()
+}
+
+pub fn write_metadata(root: &Path, name: &str, contents: &str) -> io::Result<()> {
+    let dir = metadata_dir(root);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(name), contents)
+}
+
+// ワークスペースからの相対パス（区切りは常に "/"）
+pub fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+// ワークスペースのパスを相対パスから復元
+pub fn resolve_key(root: &Path, key: &str) -> PathBuf {
+    key.split('/')
+        .fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+const STATE_FILE: &str = "state";
+
+// ツリーの開閉・選択・スクロール位置などの状態（1行に「キー=値」）
+#[derive(Default, PartialEq)]
+pub struct WorkspaceState {
+    pub expanded: Vec<String>,
+    pub selected: Vec<String>,
+    pub active: Option<String>,
+    pub open_file: Option<String>,
+    pub scroll: f32,
+    pub settings: Vec<(String, String)>,
+}
+
+impl WorkspaceState {
+    pub fn load(root: &Path) -> Self {
+        let mut state = Self::default();
+        let Some(contents) = read_metadata(root, STATE_FILE) else {
+            return state;
+        };
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key {
+                "expanded" => state.expanded.push(value.to_string()),
+                "selected" => state.selected.push(value.to_string()),
+                "active" => state.active = Some(value.to_string()),
+                "open" => state.open_file = Some(value.to_string()),
+                "scroll" => state.scroll = value.parse().unwrap_or(0.0),
+                _ => state.settings.push((key.to_string(), value.to_string())),
+            }
+        }
+        state
+    }
+
+    pub fn setting(&self, key: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn settings_for<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.settings
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut lines = Vec::new();
+        lines.extend(
+            self.expanded
+                .iter()
+                .map(|path| format!("expanded={}", path)),
+        );
+        lines.extend(
+            self.selected
+                .iter()
+                .map(|path| format!("selected={}", path)),
+        );
+        if let Some(active) = &self.active {
+            lines.push(format!("active={}", active));
+        }
+        if let Some(open_file) = &self.open_file {
+            lines.push(format!("open={}", open_file));
+        }
+        lines.push(format!("scroll={}", self.scroll));
+        lines.extend(
+            self.settings
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value)),
+        );
+        lines.join("\n") + "\n"
+    }
+
+    pub fn save(&self, root: &Path) -> io::Result<()> {
+        write_metadata(root, STATE_FILE, &self.serialize())
+    }
+}